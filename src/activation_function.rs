@@ -1,86 +1,166 @@
+use crate::config::ActivationConfig;
+use crate::float::Float;
 use crate::matrix::Matrix;
 
-pub trait ActivationFunction {
-    fn forward(&self, input: &Matrix) -> Matrix{
+pub trait ActivationFunction<T: Float = f64>: Send + Sync {
+    fn forward(&self, input: &Matrix<T>) -> Matrix<T>{
         let mut output = input.clone();
-        let output_data = output.get_data_mut();
-        for i in 0..output_data.len() {
-            output_data[i] = self.function(output_data[i]);
+        for value in output.get_data_mut().iter_mut() {
+            *value = self.function(*value);
         }
         output
     }
-    fn backwards(&self, input: &Matrix) -> Matrix{
+    fn backwards(&self, input: &Matrix<T>) -> Matrix<T>{
         let mut output = input.clone();
-        let output_data = output.get_data_mut();
-        for i in 0..output_data.len() {
-            output_data[i] = self.derivative(output_data[i]);
+        for value in output.get_data_mut().iter_mut() {
+            *value = self.derivative(*value);
         }
         output
     }
-    fn function(&self, x: f64) -> f64;
-    fn derivative(&self, x: f64) -> f64;
+    fn function(&self, x: T) -> T;
+    fn derivative(&self, x: T) -> T;
+    fn name(&self) -> String {
+        "custom".to_string()
+    }
+    /// Description used by `NN::get_config`, `None` for functions it cannot express.
+    fn config(&self) -> Option<ActivationConfig> {
+        None
+    }
 }
 
 pub struct Sigmoid;
-impl ActivationFunction for Sigmoid {
-    fn function(&self, x: f64) -> f64 {
-        1.0 / (1.0 + (-x).exp())
+impl<T: Float> ActivationFunction<T> for Sigmoid {
+    fn function(&self, x: T) -> T {
+        T::one() / (T::one() + (-x).exp())
     }
 
-    fn derivative(&self, x: f64) -> f64 {
+    fn derivative(&self, x: T) -> T {
         let s = self.function(x);
-        s * (1.0 - s)
+        s * (T::one() - s)
+    }
+
+    fn name(&self) -> String {
+        "sigmoid".to_string()
+    }
+
+    fn config(&self) -> Option<ActivationConfig> {
+        Some(ActivationConfig::Sigmoid)
     }
 }
 
 pub struct ReLU;
-impl ActivationFunction for ReLU {
-    fn function(&self, x: f64) -> f64 {
-        if x > 0.0 {
+impl<T: Float> ActivationFunction<T> for ReLU {
+    fn function(&self, x: T) -> T {
+        if x > T::zero() {
             x
         } else {
-            0.0
+            T::zero()
         }
     }
 
-    fn derivative(&self, x: f64) -> f64 {
-        if x > 0.0 {
-            1.0
+    fn derivative(&self, x: T) -> T {
+        if x > T::zero() {
+            T::one()
         } else {
-            0.0
+            T::zero()
         }
     }
+
+    fn name(&self) -> String {
+        "relu".to_string()
+    }
+
+    fn config(&self) -> Option<ActivationConfig> {
+        Some(ActivationConfig::Relu)
+    }
+}
+
+pub struct LeakyReLU<T: Float = f64>{
+    alpha: T
 }
 
-pub struct LeakyReLU{
-    alpha: f64
+impl<T: Float> LeakyReLU<T> {
+    pub fn new(alpha: T) -> LeakyReLU<T> {
+        LeakyReLU {alpha}
+    }
 }
 
-impl ActivationFunction for LeakyReLU {
-    fn function(&self, x: f64) -> f64 {
-        if x > 0.0 {
+impl<T: Float> ActivationFunction<T> for LeakyReLU<T> {
+    fn function(&self, x: T) -> T {
+        if x > T::zero() {
             x
         } else {
             self.alpha * x
         }
     }
 
-    fn derivative(&self, x: f64) -> f64 {
-        if x > 0.0 {
-            1.0
+    fn derivative(&self, x: T) -> T {
+        if x > T::zero() {
+            T::one()
         } else {
             self.alpha
         }
     }
+
+    fn name(&self) -> String {
+        "leaky_relu".to_string()
+    }
+
+    fn config(&self) -> Option<ActivationConfig> {
+        Some(ActivationConfig::LeakyRelu {alpha: self.alpha.to_f64()})
+    }
 }
 pub struct Tanh;
-impl ActivationFunction for Tanh {
-    fn function(&self, x: f64) -> f64 {
+impl<T: Float> ActivationFunction<T> for Tanh {
+    fn function(&self, x: T) -> T {
         x.tanh()
     }
 
-    fn derivative(&self, x: f64) -> f64 {
+    fn derivative(&self, x: T) -> T {
         let t = self.function(x);
-        1.0 - t * t
+        T::one() - t * t
+    }
+
+    fn name(&self) -> String {
+        "tanh".to_string()
+    }
+
+    fn config(&self) -> Option<ActivationConfig> {
+        Some(ActivationConfig::Tanh)
+    }
+}
+
+#[cfg(test)]
+mod test_activation_function {
+    use super::*;
+    use crate::gradcheck::check_layer;
+    use crate::layers::{activation_layer::ActivationLayer, layer_interface::Layer};
+
+    fn check(activation_function: Box<dyn ActivationFunction>) -> f64 {
+        let mut layer = ActivationLayer::new(activation_function);
+        layer.initialize([1, 6]).unwrap();
+        // keeps clear of the kink at zero
+        let input = Matrix::from_vec(vec![-2.5, -1.0, -0.1, 0.2, 1.3, 3.0], 1, 6).unwrap();
+        check_layer(&mut layer, &input, 1e-6).unwrap().max_error()
+    }
+
+    #[test]
+    fn test_sigmoid() {
+        assert!(check(Box::new(Sigmoid{})) < 1e-6);
+    }
+
+    #[test]
+    fn test_relu() {
+        assert!(check(Box::new(ReLU{})) < 1e-6);
+    }
+
+    #[test]
+    fn test_leaky_relu() {
+        assert!(check(Box::new(LeakyReLU::new(0.1))) < 1e-6);
+    }
+
+    #[test]
+    fn test_tanh() {
+        assert!(check(Box::new(Tanh{})) < 1e-6);
     }
 }
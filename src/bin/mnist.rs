@@ -0,0 +1,153 @@
+use std::env;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::str::FromStr;
+use neural_network::callbacks::{Action, Callback, EpochLogs};
+use neural_network::dataset::{Dataset, Subset};
+use neural_network::error::Result;
+use neural_network::initializers::Initializer;
+use neural_network::layers::dense_layer::DenseLayer;
+use neural_network::metrics::Metric;
+use neural_network::metrics::classification::Accuracy;
+use neural_network::mnist::{self, NUM_CLASSES};
+use neural_network::neural_network::NN;
+use neural_network::training::TrainConfig;
+
+const USAGE: &str = "\
+Usage: mnist [options]
+
+Trains a dense network and a small CNN on MNIST and reports the training loss and
+test accuracy after every epoch.
+
+Options:
+  --model <dense|cnn|both>  networks to train (default both)
+  --epochs <n>              passes over the training set (default 5)
+  --batch-size <n>          samples per update (default 32)
+  --learning-rate <rate>    gradient descent step size (default 0.5)
+  --train-samples <n>       use only the first n training images (default all)
+  --test-samples <n>        use only the first n test images (default all)
+  --workers <n>             training threads (default 1)
+  --seed <n>                seed for shuffling the training set
+  --output <directory>      where mnist_<model>.json is saved (default .)";
+
+struct Options {
+    models: Vec<&'static str>,
+    epochs: u64,
+    batch_size: usize,
+    learning_rate: f64,
+    train_samples: Option<usize>,
+    test_samples: Option<usize>,
+    workers: usize,
+    seed: Option<u64>,
+    output: PathBuf
+}
+
+fn parse_value<V: FromStr>(name: &str, value: Option<String>) -> std::result::Result<V, String> {
+    let value = value.ok_or_else(|| format!("{} requires a value", name))?;
+    value.parse().map_err(|_| format!("invalid value '{}' for {}", value, name))
+}
+
+fn parse_options(args: impl IntoIterator<Item = String>) -> std::result::Result<Option<Options>, String> {
+    let mut options = Options {models: vec!["dense", "cnn"], epochs: 5, batch_size: 32, learning_rate: 0.5,
+        train_samples: None, test_samples: None, workers: 1, seed: None, output: PathBuf::from(".")};
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--model" => options.models = match parse_value::<String>(&arg, args.next())?.as_str() {
+                "dense" => vec!["dense"],
+                "cnn" => vec!["cnn"],
+                "both" => vec!["dense", "cnn"],
+                model => return Err(format!("unknown model '{}', expected dense, cnn or both", model))
+            },
+            "--epochs" => options.epochs = parse_value(&arg, args.next())?,
+            "--batch-size" => options.batch_size = parse_value(&arg, args.next())?,
+            "--learning-rate" => options.learning_rate = parse_value(&arg, args.next())?,
+            "--train-samples" => options.train_samples = Some(parse_value(&arg, args.next())?),
+            "--test-samples" => options.test_samples = Some(parse_value(&arg, args.next())?),
+            "--workers" => options.workers = parse_value(&arg, args.next())?,
+            "--seed" => options.seed = Some(parse_value(&arg, args.next())?),
+            "--output" => options.output = parse_value(&arg, args.next())?,
+            _ => return Err(format!("unknown option '{}'", arg))
+        }
+    }
+    Ok(Some(options))
+}
+
+fn build(model: &str, learning_rate: f64) -> Result<NN> {
+    let output = DenseLayer::new(NUM_CLASSES).kernel_initializer(Initializer::GlorotUniform).bias_initializer(Initializer::Zeros);
+    let builder = NN::builder([28, 28]).learning_rate(learning_rate);
+    let builder = match model {
+        "dense" => builder.flatten()
+            .layer(Box::new(DenseLayer::new(64).kernel_initializer(Initializer::HeUniform).bias_initializer(Initializer::Zeros)))
+            .relu(),
+        _ => builder.convolutional([3, 3], 1, 0).relu()
+            .convolutional([3, 3], 2, 0).relu()
+            .flatten()
+    };
+    builder.layer(Box::new(output)).sigmoid().build()
+}
+
+/// Prints the epoch's training loss with the loss and accuracy on the test set.
+struct TestAccuracy<'a> {
+    test: &'a dyn Dataset
+}
+
+impl Callback for TestAccuracy<'_> {
+    fn on_epoch_end(&mut self, nn: &mut NN, logs: &EpochLogs) -> Result<Action> {
+        let metrics: [Box<dyn Metric>; 1] = [Box::new(Accuracy)];
+        let evaluation = nn.evaluate(self.test, &metrics)?;
+        println!("epoch {}: loss {:.4}, test loss {:.4}, test accuracy {:.2}%", logs.epoch + 1, logs.loss, evaluation.loss,
+            100.0 * evaluation.get_metric("accuracy").unwrap_or(0.0));
+        Ok(Action::Continue)
+    }
+}
+
+fn first<D: Dataset>(dataset: &D, samples: Option<usize>) -> Result<Subset<'_, D>> {
+    let len = samples.unwrap_or(dataset.len()).min(dataset.len());
+    Subset::new(dataset, (0..len).collect())
+}
+
+fn run(options: &Options) -> Result<()> {
+    let train_dataset = mnist::load_train_dataset::<f64>()?;
+    let test_dataset = mnist::load_test_dataset::<f64>()?;
+    let train = first(&train_dataset, options.train_samples)?;
+    let test = first(&test_dataset, options.test_samples)?;
+    println!("{} training and {} test images", train.len(), test.len());
+    for &model in options.models.iter() {
+        let mut nn = build(model, options.learning_rate)?;
+        println!();
+        nn.summary();
+        let mut config = TrainConfig::new(options.epochs).batch_size(options.batch_size).shuffle(true).workers(options.workers);
+        if let Some(seed) = options.seed {
+            config = config.seed(seed);
+        }
+        let mut report = TestAccuracy {test: &test};
+        nn.fit_with_callbacks(&train, &config, &mut [&mut report])?;
+        let path = options.output.join(format!("mnist_{}.json", model));
+        nn.save(&path)?;
+        println!("Saved {} model to {}", model, path.display());
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let options = match parse_options(env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("mnist: error: {}\nRun 'mnist --help' for usage.", message);
+            return ExitCode::from(2);
+        }
+    };
+    match run(&options) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("mnist: error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
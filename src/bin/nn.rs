@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::io::{self, ErrorKind};
+use std::path::Path;
+use std::process::ExitCode;
+use std::str::FromStr;
+use neural_network::callbacks::ProgressBar;
+use neural_network::config::ModelConfig;
+use neural_network::dataset::{load_csv, read_csv, Dataset};
+use neural_network::error::Error;
+use neural_network::image::load_pgm;
+use neural_network::matrix::Matrix;
+use neural_network::metrics::Metric;
+use neural_network::metrics::classification::{predicted_class, Accuracy, ClassificationReport};
+use neural_network::metrics::regression::{MeanAbsoluteError, MeanSquaredError};
+use neural_network::mnist;
+use neural_network::neural_network::NN;
+use neural_network::training::TrainConfig;
+
+const USAGE: &str = "\
+Usage: nn <command> [options]
+
+Commands:
+  train    --config <model config> --data <dataset> [--labels <IDX labels>] --output <model file>
+           [--epochs 10] [--batch-size 32] [--learning-rate <rate>] [--seed <n>]
+           [--validation-split 0.0] [--workers 1] [--shuffle]
+  eval     --model <model file> --data <dataset> [--labels <IDX labels>]
+  predict  --model <model file> --input <file> [--format csv|idx|pgm] [--class]
+  inspect  --model <model file> | --config <model config>
+
+Datasets are CSV files with the flattened input followed by the targets on each line,
+or IDX image files in the MNIST format together with --labels. Inputs to predict are
+CSV rows, IDX image files or grayscale PGM images; the format defaults to the extension.
+
+Exit codes: 0 success, 2 invalid usage, 65 malformed data, 66 missing input file,
+70 numerical error, 74 other I/O error, 78 invalid model configuration.";
+
+enum CliError {
+    Usage(String),
+    Library(Error)
+}
+
+impl From<Error> for CliError {
+    fn from(error: Error) -> CliError {
+        CliError::Library(error)
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::Usage(message) => write!(f, "{}\nRun 'nn --help' for usage.", message),
+            CliError::Library(error) => write!(f, "{}", error)
+        }
+    }
+}
+
+impl CliError {
+    fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Usage(_) => 2,
+            CliError::Library(Error::Format(_) | Error::ShapeMismatch {..}) => 65,
+            CliError::Library(Error::Io(error)) if error.kind() == ErrorKind::NotFound => 66,
+            CliError::Library(Error::Numerical(_)) => 70,
+            CliError::Library(Error::Io(_)) => 74,
+            CliError::Library(Error::InvalidConfiguration(_)) => 78
+        }
+    }
+}
+
+type CliResult<T> = Result<T, CliError>;
+
+struct Options {
+    command: &'static str,
+    values: HashMap<String, String>,
+    switches: Vec<String>
+}
+
+impl Options {
+    /// Parses `--name value` pairs and the given value-less `switches` of `command`.
+    fn parse(command: &'static str, args: &[String], names: &[&str], switches: &[&str]) -> CliResult<Options> {
+        let mut options = Options {command, values: HashMap::new(), switches: Vec::new()};
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let Some(name) = arg.strip_prefix("--") else {
+                return Err(CliError::Usage(format!("unexpected argument '{}' for '{}'", arg, command)));
+            };
+            if switches.contains(&name) {
+                options.switches.push(name.to_string());
+            } else if names.contains(&name) {
+                let value = args.next().ok_or_else(|| CliError::Usage(format!("--{} requires a value", name)))?;
+                options.values.insert(name.to_string(), value.clone());
+            } else {
+                return Err(CliError::Usage(format!("unknown option '--{}' for '{}'", name, command)));
+            }
+        }
+        Ok(options)
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.values.get(name).map(String::as_str)
+    }
+
+    fn required(&self, name: &str) -> CliResult<&str> {
+        self.get(name).ok_or_else(|| CliError::Usage(format!("'{}' requires --{}", self.command, name)))
+    }
+
+    /// Path given for `name`, which must name an existing file.
+    fn file(&self, name: &str) -> CliResult<&str> {
+        let path = self.required(name)?;
+        check_file(path)
+    }
+
+    fn parse_value<V: FromStr>(&self, name: &str) -> CliResult<Option<V>> {
+        self.get(name).map(|value| value.parse()
+            .map_err(|_| CliError::Usage(format!("invalid value '{}' for --{}", value, name)))).transpose()
+    }
+
+    fn switch(&self, name: &str) -> bool {
+        self.switches.iter().any(|switch| switch == name)
+    }
+}
+
+fn check_file(path: &str) -> CliResult<&str> {
+    if !Path::new(path).is_file() {
+        return Err(Error::Io(io::Error::new(ErrorKind::NotFound, format!("{} does not exist", path))).into());
+    }
+    Ok(path)
+}
+
+fn load_dataset(options: &Options, nn: &NN) -> CliResult<Box<dyn Dataset>> {
+    let data = options.file("data")?;
+    let dataset: Box<dyn Dataset> = match options.get("labels") {
+        Some(labels) => Box::new(mnist::load_files(data, check_file(labels)?)?),
+        None => Box::new(load_csv(data, nn.get_input_size(), nn.get_output_size()[1])?)
+    };
+    if dataset.is_empty() {
+        return Err(Error::Format(format!("{} contains no samples", data)).into());
+    }
+    let (inputs, targets) = dataset.get(0)?;
+    if inputs.get_shape() != nn.get_input_size() || targets.get_shape() != nn.get_output_size() {
+        let [input, output] = [nn.get_input_size(), nn.get_output_size()];
+        return Err(Error::Format(format!(
+            "{} has samples of shape [{}, {}] -> [{}, {}] but the model maps [{}, {}] -> [{}, {}]", data,
+            inputs.get_num_rows(), inputs.get_num_cols(), targets.get_num_rows(), targets.get_num_cols(),
+            input[0], input[1], output[0], output[1])).into());
+    }
+    Ok(dataset)
+}
+
+fn train(args: &[String]) -> CliResult<()> {
+    let options = Options::parse("train", args, &["config", "data", "labels", "output", "epochs", "batch-size",
+        "learning-rate", "seed", "validation-split", "workers"], &["shuffle"])?;
+    let mut config = ModelConfig::load(options.file("config")?)?;
+    if let Some(learning_rate) = options.parse_value("learning-rate")? {
+        config.learning_rate = learning_rate;
+    }
+    let output = options.required("output")?;
+    let mut nn: NN = config.build()?;
+    let dataset = load_dataset(&options, &nn)?;
+    let mut train_config = TrainConfig::new(options.parse_value("epochs")?.unwrap_or(10))
+        .batch_size(options.parse_value("batch-size")?.unwrap_or(32))
+        .validation_split(options.parse_value("validation-split")?.unwrap_or(0.0))
+        .workers(options.parse_value("workers")?.unwrap_or(1))
+        .shuffle(options.switch("shuffle"));
+    if let Some(seed) = options.parse_value("seed")? {
+        train_config = train_config.seed(seed);
+    }
+    let mut progress = ProgressBar::new();
+    nn.fit_with_callbacks(dataset.as_ref(), &train_config, &mut [&mut progress])?;
+    nn.save(output)?;
+    println!("Saved model to {}", output);
+    Ok(())
+}
+
+fn eval(args: &[String]) -> CliResult<()> {
+    let options = Options::parse("eval", args, &["model", "data", "labels"], &[])?;
+    let nn: NN = NN::load(options.file("model")?)?;
+    let dataset = load_dataset(&options, &nn)?;
+    let mut metrics: Vec<Box<dyn Metric>> = vec![Box::new(MeanSquaredError), Box::new(MeanAbsoluteError)];
+    let classification = nn.get_output_size()[1] > 1;
+    if classification {
+        metrics.push(Box::new(Accuracy));
+    }
+    let evaluation = nn.evaluate(dataset.as_ref(), &metrics)?;
+    println!("samples: {}", dataset.len());
+    println!("loss: {:.6}", evaluation.loss);
+    for (name, value) in evaluation.metrics.iter() {
+        println!("{}: {:.6}", name, value);
+    }
+    if classification {
+        let (predictions, targets) = nn.predict_dataset(dataset.as_ref())?;
+        println!();
+        print!("{}", ClassificationReport::new(&predictions, &targets)?);
+    }
+    Ok(())
+}
+
+fn read_inputs(path: &str, format: &str, nn: &NN) -> CliResult<Vec<Matrix>> {
+    let [rows, cols] = nn.get_input_size();
+    match format {
+        "csv" => read_csv(path)?.into_iter().enumerate().map(|(index, row)| {
+            if row.len() != rows * cols {
+                return Err(Error::Format(format!("sample {}: expected {} values, got {}", index + 1, rows * cols, row.len())).into());
+            }
+            Ok(Matrix::from_vec(row, rows, cols)?)
+        }).collect(),
+        "idx" => Ok(mnist::load_images(path)?),
+        "pgm" => Ok(vec![load_pgm(path)?]),
+        _ => Err(CliError::Usage(format!("unknown input format '{}', expected csv, idx or pgm", format)))
+    }
+}
+
+fn predict(args: &[String]) -> CliResult<()> {
+    let options = Options::parse("predict", args, &["model", "input", "format"], &["class"])?;
+    let nn: NN = NN::load(options.file("model")?)?;
+    let input = options.file("input")?;
+    let extension = Path::new(input).extension().and_then(|extension| extension.to_str()).unwrap_or("idx");
+    let format = options.get("format").unwrap_or(extension).to_lowercase();
+    for inputs in read_inputs(input, &format, &nn)? {
+        if inputs.get_shape() != nn.get_input_size() {
+            let ([rows, cols], [expected_rows, expected_cols]) = (inputs.get_shape(), nn.get_input_size());
+            return Err(Error::Format(format!("{} has inputs of shape [{}, {}] but the model expects [{}, {}]",
+                input, rows, cols, expected_rows, expected_cols)).into());
+        }
+        let outputs = nn.predict(&inputs)?;
+        if options.switch("class") {
+            println!("{}", predicted_class(&outputs));
+        } else {
+            let values: Vec<String> = outputs.get_data().iter().map(|value| value.to_string()).collect();
+            println!("{}", values.join(","));
+        }
+    }
+    Ok(())
+}
+
+fn inspect(args: &[String]) -> CliResult<()> {
+    let options = Options::parse("inspect", args, &["model", "config"], &[])?;
+    let nn: NN = match (options.get("model"), options.get("config")) {
+        (Some(model), None) => NN::load(check_file(model)?)?,
+        (None, Some(config)) => ModelConfig::load(check_file(config)?)?.build()?,
+        _ => return Err(CliError::Usage("'inspect' requires exactly one of --model and --config".to_string()))
+    };
+    nn.summary();
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("train") => train(&args[1..]),
+        Some("eval") => eval(&args[1..]),
+        Some("predict") => predict(&args[1..]),
+        Some("inspect") => inspect(&args[1..]),
+        Some("-h" | "--help" | "help") => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Some(command) => Err(CliError::Usage(format!("unknown command '{}'", command))),
+        None => Err(CliError::Usage("missing command".to_string()))
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("nn: error: {}", error);
+            ExitCode::from(error.exit_code())
+        }
+    }
+}
@@ -1,33 +1,37 @@
-use neural_network::layers::{dense_layer::DenseLayer, activation_layer::ActivationLayer};
+use neural_network::callbacks::ProgressBar;
+use neural_network::dataset::VecDataset;
+use neural_network::error::Result;
 use neural_network::matrix::Matrix;
 use neural_network::neural_network::NN;
-use neural_network::activation_function::*;
+use neural_network::training::TrainConfig;
 
-fn vec_to_matrix(vec: Vec<f64>) -> Matrix {
+fn vec_to_matrix(vec: Vec<f64>) -> Result<Matrix> {
     let len = vec.len();
     Matrix::from_vec(vec, 1, len)
 }
 
-fn main() {
-    let mut nn: NN = NN::new([1, 2], 0.1);
-    let layer_sizes = vec![4, 8, 4];
-    for i in 1..layer_sizes.len() {
-        nn.add(Box::new(DenseLayer::new(layer_sizes[i])));
-        nn.add(Box::new(ActivationLayer::new(Box::new(ReLU{}))));
-    }
-    nn.add(Box::new(DenseLayer::new(2)));
-    nn.add(Box::new(ActivationLayer::new(Box::new(Sigmoid{}))));
+fn main() -> Result<()> {
+    let mut nn: NN = NN::builder([1, 2]).learning_rate(0.1)
+        .dense(8).relu()
+        .dense(4).relu()
+        .dense(2).sigmoid()
+        .build()?;
+    nn.summary();
+
+    let x_train = [vec![0.0, 0.0], vec![0.0, 1.0], vec![1.0, 0.0], vec![1.0, 1.0]];
+    let x_train = x_train.iter().map(|x| vec_to_matrix(x.clone())).collect::<Result<Vec<_>>>()?;
+    let y_train = [vec![1.0, 0.0], vec![0.0, 1.0], vec![0.0, 1.0], vec![1.0, 0.0]];
+    let y_train = y_train.iter().map(|x| vec_to_matrix(x.clone())).collect::<Result<Vec<_>>>()?;
 
-    let x_train = vec![vec![0.0, 0.0], vec![0.0, 1.0], vec![1.0, 0.0], vec![1.0, 1.0]];
-    let x_train = x_train.iter().map(|x| vec_to_matrix(x.clone())).collect();
-    let y_train = vec![vec![1.0, 0.0], vec![0.0, 1.0], vec![0.0, 1.0], vec![1.0, 0.0]];
-    let y_train = y_train.iter().map(|x| vec_to_matrix(x.clone())).collect();
+    let dataset = VecDataset::new(x_train, y_train)?;
 
-    match nn.train(&x_train, &y_train, 10000) {
+    let mut progress = ProgressBar::new().every(1000);
+    match nn.fit_with_callbacks(&dataset, &TrainConfig::new(10000), &mut [&mut progress]) {
         Ok(_) => println!("Training complete"),
         Err(e) => println!("Error: {}", e)
     }
-    for inputs in x_train.iter() {
-        println!("Input: {:?}, Model_Output: {:?}", inputs.get_data(), nn.predict(inputs).get_data());
+    for inputs in dataset.get_inputs().iter() {
+        println!("Input: {:?}, Model_Output: {:?}", inputs.get_data(), nn.predict(inputs)?.get_data());
     }
+    Ok(())
 }
@@ -0,0 +1,154 @@
+use crate::config::{ActivationConfig, LayerConfig};
+use crate::error::{Error, Result};
+use crate::float::Float;
+use crate::initializers::Initializer;
+use crate::layers::layer_interface::Layer;
+use crate::neural_network::NN;
+
+enum LayerSpec<T: Float> {
+    Config(LayerConfig),
+    Custom(Box<dyn Layer<T>>)
+}
+
+/// Fluent construction of a sequential `NN`, started with `NN::builder`. Layers are
+/// only created and checked against their input shapes in `build`.
+pub struct NNBuilder<T: Float = f64> {
+    input_size: [usize; 2],
+    learning_rate: T,
+    layers: Vec<LayerSpec<T>>
+}
+
+// message of a nested error without its "Invalid configuration: " prefix
+fn describe(error: Error) -> String {
+    match error {
+        Error::InvalidConfiguration(message) => message,
+        error => error.to_string()
+    }
+}
+
+impl<T: Float> NNBuilder<T> {
+    /// Builder for a network taking `input_size` inputs, with a learning rate of 0.1.
+    pub fn new(input_size: [usize; 2]) -> NNBuilder<T> {
+        NNBuilder {input_size, learning_rate: T::from_f64(0.1), layers: Vec::new()}
+    }
+
+    pub fn learning_rate(mut self, learning_rate: T) -> NNBuilder<T> {
+        self.learning_rate = learning_rate;
+        self
+    }
+
+    /// Appends a layer described by a config.
+    pub fn config(mut self, layer: LayerConfig) -> NNBuilder<T> {
+        self.layers.push(LayerSpec::Config(layer));
+        self
+    }
+
+    /// Appends an already constructed layer.
+    pub fn layer(mut self, layer: Box<dyn Layer<T>>) -> NNBuilder<T> {
+        self.layers.push(LayerSpec::Custom(layer));
+        self
+    }
+
+    pub fn dense(self, units: usize) -> NNBuilder<T> {
+        self.config(LayerConfig::Dense {units, kernel_initializer: Initializer::default(), bias_initializer: Initializer::default(),
+            kernel_regularizer: None, bias_regularizer: None, kernel_constraint: None, bias_constraint: None})
+    }
+
+    pub fn convolutional(self, kernel_size: [usize; 2], stride: usize, padding: usize) -> NNBuilder<T> {
+        self.config(LayerConfig::Convolutional {kernel_size, stride, padding, initializer: Initializer::default(),
+            kernel_regularizer: None, kernel_constraint: None})
+    }
+
+    pub fn flatten(self) -> NNBuilder<T> {
+        self.config(LayerConfig::Flatten)
+    }
+
+    pub fn activation(self, activation: ActivationConfig) -> NNBuilder<T> {
+        self.config(LayerConfig::Activation {activation})
+    }
+
+    pub fn sigmoid(self) -> NNBuilder<T> {
+        self.activation(ActivationConfig::Sigmoid)
+    }
+
+    pub fn relu(self) -> NNBuilder<T> {
+        self.activation(ActivationConfig::Relu)
+    }
+
+    pub fn leaky_relu(self, alpha: f64) -> NNBuilder<T> {
+        self.activation(ActivationConfig::LeakyRelu {alpha})
+    }
+
+    pub fn tanh(self) -> NNBuilder<T> {
+        self.activation(ActivationConfig::Tanh)
+    }
+
+    /// Creates and initializes every layer. Errors name the index and type of the
+    /// first layer that is invalid or does not fit the output of the previous one.
+    pub fn build(self) -> Result<NN<T>> {
+        let learning_rate = self.learning_rate.to_f64();
+        if !(learning_rate.is_finite() && learning_rate > 0.0) {
+            return Err(Error::InvalidConfiguration(format!("learning_rate must be positive, got {}", learning_rate)));
+        }
+        if self.input_size[0] == 0 || self.input_size[1] == 0 {
+            return Err(Error::InvalidConfiguration(
+                format!("input_size must be positive, got [{}, {}]", self.input_size[0], self.input_size[1])));
+        }
+        let mut nn = NN::new(self.input_size, self.learning_rate);
+        for (index, spec) in self.layers.into_iter().enumerate() {
+            let (name, layer) = match spec {
+                LayerSpec::Config(config) => (config.get_type().to_string(), config.build()),
+                LayerSpec::Custom(layer) => (layer.name(), Ok(layer))
+            };
+            layer.and_then(|layer| nn.add(layer))
+                .map_err(|error| Error::InvalidConfiguration(format!("layer {} ({}): {}", index, name, describe(error))))?;
+        }
+        Ok(nn)
+    }
+}
+
+/// Builds a sequential `NN` from an input size followed by `NNBuilder` method calls,
+/// e.g. `sequential![[1, 2]; learning_rate(0.1), dense(8), relu(), dense(2), sigmoid()]`.
+/// Evaluates to the `Result` of `NNBuilder::build`.
+#[macro_export]
+macro_rules! sequential {
+    ($input_size:expr; $($method:ident($($arg:expr),*)),* $(,)?) => {
+        $crate::neural_network::NN::builder($input_size)$(.$method($($arg),*))*.build()
+    };
+}
+
+#[cfg(test)]
+mod test_builder {
+    use super::*;
+    use crate::layers::flatten_layer::FlattenLayer;
+    use crate::matrix::Matrix;
+
+    #[test]
+    fn test_build() {
+        let nn: NN = NN::builder([1, 2]).dense(8).relu().dense(2).sigmoid().build().unwrap();
+        let summary = nn.get_summary();
+        assert_eq!(summary.layers.len(), 4);
+        assert_eq!(summary.layers[3].output_size, [1, 2]);
+        let from_macro: NN = crate::sequential![[1, 2]; dense(8), relu(), dense(2), sigmoid()].unwrap();
+        assert_eq!(from_macro.get_config().unwrap(), nn.get_config().unwrap());
+    }
+
+    #[test]
+    fn test_custom_layer() {
+        let nn: NN = NN::builder([3, 3]).convolutional([2, 2], 1, 0).tanh().layer(Box::new(FlattenLayer::new())).dense(1)
+            .learning_rate(0.5).build().unwrap();
+        assert_eq!(nn.predict(&Matrix::new(3, 3)).unwrap().get_shape(), [1, 1]);
+        assert_eq!(nn.get_config().unwrap().learning_rate, 0.5);
+    }
+
+    #[test]
+    fn test_shape_errors() {
+        // a dense layer needs a [1, n] input, so the missing flatten is reported at build
+        let error = NN::<f64>::builder([3, 3]).convolutional([2, 2], 1, 0).dense(4).build().err().unwrap();
+        assert!(error.to_string().contains("layer 1 (dense)"), "{}", error);
+        let error = NN::<f64>::builder([2, 2]).convolutional([3, 3], 1, 0).build().err().unwrap();
+        assert!(error.to_string().contains("layer 0 (convolutional)"), "{}", error);
+        assert!(NN::<f64>::builder([1, 2]).dense(0).build().is_err());
+        assert!(NN::<f64>::builder([1, 2]).learning_rate(0.0).build().is_err());
+    }
+}
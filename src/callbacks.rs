@@ -0,0 +1,454 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use crate::error::{Error, Result};
+use crate::float::Float;
+use crate::matrix::Matrix;
+use crate::neural_network::NN;
+use crate::training::{Evaluation, History};
+
+/// Returned by the end-of-batch and end-of-epoch hooks to let a callback stop training.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    Continue,
+    Stop
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct TrainingParams {
+    pub epochs: u64,
+    pub num_batches: usize,
+    pub num_samples: usize
+}
+
+#[derive(Clone, Debug)]
+pub struct BatchLogs<T: Float = f64> {
+    pub epoch: u64,
+    pub batch: usize,
+    pub size: usize,
+    /// Mean loss of the samples in this batch.
+    pub loss: T,
+    /// Global norm of the parameter gradients before clipping, if any layer reports gradients.
+    pub gradient_norm: Option<T>
+}
+
+#[derive(Clone, Debug)]
+pub struct EpochLogs<T: Float = f64> {
+    pub epoch: u64,
+    pub loss: T,
+    pub validation: Option<Evaluation<T>>
+}
+
+impl<T: Float> EpochLogs<T> {
+    fn format(&self) -> String {
+        let mut line = format!("loss: {:.4}", self.loss);
+        if let Some(validation) = &self.validation {
+            line += &format!(" - val_loss: {:.4}", validation.loss);
+            for (name, value) in validation.metrics.iter() {
+                line += &format!(" - val_{}: {:.4}", name, value);
+            }
+        }
+        line
+    }
+}
+
+/// Hooks invoked by `NN::fit_with_callbacks`. Every hook has access to the model,
+/// so callbacks can inspect or replace its weights.
+pub trait Callback<T: Float = f64> {
+    fn on_train_begin(&mut self, _nn: &mut NN<T>, _params: &TrainingParams) -> Result<()> {
+        Ok(())
+    }
+    fn on_train_end(&mut self, _nn: &mut NN<T>, _history: &History<T>) -> Result<()> {
+        Ok(())
+    }
+    fn on_epoch_begin(&mut self, _nn: &mut NN<T>, _epoch: u64) -> Result<()> {
+        Ok(())
+    }
+    fn on_epoch_end(&mut self, _nn: &mut NN<T>, _logs: &EpochLogs<T>) -> Result<Action> {
+        Ok(Action::Continue)
+    }
+    fn on_batch_begin(&mut self, _nn: &mut NN<T>, _epoch: u64, _batch: usize) -> Result<()> {
+        Ok(())
+    }
+    fn on_batch_end(&mut self, _nn: &mut NN<T>, _logs: &BatchLogs<T>) -> Result<Action> {
+        Ok(Action::Continue)
+    }
+}
+
+/// Quantity watched by `EarlyStopping` and `ModelCheckpoint`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Monitor {
+    Loss,
+    ValidationLoss,
+    ValidationMetric {name: String, maximize: bool}
+}
+
+impl Monitor {
+    fn value<T: Float>(&self, logs: &EpochLogs<T>) -> Result<f64> {
+        let validation = || logs.validation.as_ref()
+            .ok_or_else(|| Error::InvalidConfiguration(format!("Monitoring {:?} requires a validation split", self)));
+        match self {
+            Monitor::Loss => Ok(logs.loss.to_f64()),
+            Monitor::ValidationLoss => Ok(validation()?.loss.to_f64()),
+            Monitor::ValidationMetric {name, ..} => validation()?.get_metric(name).map(|x| x.to_f64())
+                .ok_or_else(|| Error::InvalidConfiguration(format!("Metric {} is not computed during validation", name)))
+        }
+    }
+
+    fn is_improvement(&self, value: f64, best: Option<f64>, min_delta: f64) -> bool {
+        match best {
+            _ if value.is_nan() => false,
+            None => true,
+            Some(best) => match self {
+                Monitor::ValidationMetric {maximize: true, ..} => value > best + min_delta,
+                _ => value < best - min_delta
+            }
+        }
+    }
+}
+
+/// Stops training once the monitored value has not improved by more than
+/// `min_delta` for `patience` epochs. With `restore_best_weights`, the model ends
+/// training with the weights of its best epoch.
+pub struct EarlyStopping<T: Float = f64> {
+    monitor: Monitor,
+    patience: usize,
+    min_delta: f64,
+    restore_best_weights: bool,
+    best: Option<f64>,
+    best_weights: Option<Vec<Matrix<T>>>,
+    wait: usize,
+    stopped_epoch: Option<u64>
+}
+
+impl<T: Float> EarlyStopping<T> {
+    pub fn new(patience: usize) -> EarlyStopping<T> {
+        EarlyStopping {
+            monitor: Monitor::ValidationLoss,
+            patience,
+            min_delta: 0.0,
+            restore_best_weights: false,
+            best: None,
+            best_weights: None,
+            wait: 0,
+            stopped_epoch: None
+        }
+    }
+
+    pub fn monitor(mut self, monitor: Monitor) -> EarlyStopping<T> {
+        self.monitor = monitor;
+        self
+    }
+
+    pub fn min_delta(mut self, min_delta: f64) -> EarlyStopping<T> {
+        self.min_delta = min_delta;
+        self
+    }
+
+    pub fn restore_best_weights(mut self, restore_best_weights: bool) -> EarlyStopping<T> {
+        self.restore_best_weights = restore_best_weights;
+        self
+    }
+
+    pub fn get_best(&self) -> Option<f64> {
+        self.best
+    }
+
+    pub fn get_stopped_epoch(&self) -> Option<u64> {
+        self.stopped_epoch
+    }
+}
+
+impl<T: Float> Callback<T> for EarlyStopping<T> {
+    fn on_train_begin(&mut self, _nn: &mut NN<T>, _params: &TrainingParams) -> Result<()> {
+        self.best = None;
+        self.best_weights = None;
+        self.wait = 0;
+        self.stopped_epoch = None;
+        Ok(())
+    }
+    fn on_epoch_end(&mut self, nn: &mut NN<T>, logs: &EpochLogs<T>) -> Result<Action> {
+        let value = self.monitor.value(logs)?;
+        if self.monitor.is_improvement(value, self.best, self.min_delta) {
+            self.best = Some(value);
+            self.wait = 0;
+            if self.restore_best_weights {
+                self.best_weights = Some(nn.get_weights());
+            }
+            return Ok(Action::Continue);
+        }
+        self.wait += 1;
+        if self.wait >= self.patience {
+            self.stopped_epoch = Some(logs.epoch);
+            return Ok(Action::Stop);
+        }
+        Ok(Action::Continue)
+    }
+    fn on_train_end(&mut self, nn: &mut NN<T>, _history: &History<T>) -> Result<()> {
+        if let Some(weights) = self.best_weights.take() {
+            nn.set_weights(weights)?;
+        }
+        Ok(())
+    }
+}
+
+/// Saves the model weights after each epoch. `{epoch}` in the path is replaced by
+/// the epoch number; with `save_best_only`, only improvements of the monitored value are saved.
+pub struct ModelCheckpoint {
+    path: String,
+    monitor: Monitor,
+    save_best_only: bool,
+    best: Option<f64>
+}
+
+impl ModelCheckpoint {
+    pub fn new(path: impl Into<String>) -> ModelCheckpoint {
+        ModelCheckpoint {path: path.into(), monitor: Monitor::ValidationLoss, save_best_only: false, best: None}
+    }
+
+    pub fn monitor(mut self, monitor: Monitor) -> ModelCheckpoint {
+        self.monitor = monitor;
+        self
+    }
+
+    pub fn save_best_only(mut self, save_best_only: bool) -> ModelCheckpoint {
+        self.save_best_only = save_best_only;
+        self
+    }
+}
+
+impl<T: Float> Callback<T> for ModelCheckpoint {
+    fn on_train_begin(&mut self, _nn: &mut NN<T>, _params: &TrainingParams) -> Result<()> {
+        self.best = None;
+        Ok(())
+    }
+    fn on_epoch_end(&mut self, nn: &mut NN<T>, logs: &EpochLogs<T>) -> Result<Action> {
+        if self.save_best_only {
+            let value = self.monitor.value(logs)?;
+            if !self.monitor.is_improvement(value, self.best, 0.0) {
+                return Ok(Action::Continue);
+            }
+            self.best = Some(value);
+        }
+        nn.save_weights(self.path.replace("{epoch}", &logs.epoch.to_string()))?;
+        Ok(Action::Continue)
+    }
+}
+
+/// Draws a progress bar on stdout for every `every`-th epoch and the last one.
+pub struct ProgressBar {
+    width: usize,
+    every: u64,
+    epochs: u64,
+    num_batches: usize
+}
+
+impl ProgressBar {
+    pub fn new() -> ProgressBar {
+        ProgressBar {width: 30, every: 1, epochs: 0, num_batches: 0}
+    }
+
+    pub fn every(mut self, every: u64) -> ProgressBar {
+        self.every = every.max(1);
+        self
+    }
+
+    pub fn width(mut self, width: usize) -> ProgressBar {
+        self.width = width;
+        self
+    }
+
+    fn is_shown(&self, epoch: u64) -> bool {
+        epoch.is_multiple_of(self.every) || epoch + 1 == self.epochs
+    }
+
+    fn bar(&self, done: usize) -> String {
+        let filled = self.width * done / self.num_batches.max(1);
+        let mut bar = "=".repeat(filled);
+        if filled < self.width {
+            bar.push('>');
+            bar += &" ".repeat(self.width - filled - 1);
+        }
+        bar
+    }
+}
+
+impl Default for ProgressBar {
+    fn default() -> ProgressBar {
+        ProgressBar::new()
+    }
+}
+
+impl<T: Float> Callback<T> for ProgressBar {
+    fn on_train_begin(&mut self, _nn: &mut NN<T>, params: &TrainingParams) -> Result<()> {
+        self.epochs = params.epochs;
+        self.num_batches = params.num_batches;
+        Ok(())
+    }
+    fn on_batch_end(&mut self, _nn: &mut NN<T>, logs: &BatchLogs<T>) -> Result<Action> {
+        if self.is_shown(logs.epoch) {
+            print!("\rEpoch {}/{} [{}] {}/{} batch_loss: {:.4}", logs.epoch + 1, self.epochs,
+                self.bar(logs.batch + 1), logs.batch + 1, self.num_batches, logs.loss);
+            std::io::stdout().flush()?;
+        }
+        Ok(Action::Continue)
+    }
+    fn on_epoch_end(&mut self, _nn: &mut NN<T>, logs: &EpochLogs<T>) -> Result<Action> {
+        if self.is_shown(logs.epoch) {
+            println!("\rEpoch {}/{} [{}] {}/{} {}", logs.epoch + 1, self.epochs,
+                self.bar(self.num_batches), self.num_batches, self.num_batches, logs.format());
+        }
+        Ok(Action::Continue)
+    }
+}
+
+/// Appends one row per epoch to a CSV file, with a column for every validation metric.
+pub struct CsvLogger {
+    path: PathBuf,
+    writer: Option<BufWriter<File>>
+}
+
+impl CsvLogger {
+    pub fn new(path: impl Into<PathBuf>) -> CsvLogger {
+        CsvLogger {path: path.into(), writer: None}
+    }
+}
+
+impl<T: Float> Callback<T> for CsvLogger {
+    fn on_train_begin(&mut self, _nn: &mut NN<T>, _params: &TrainingParams) -> Result<()> {
+        self.writer = None;
+        Ok(())
+    }
+    fn on_epoch_end(&mut self, _nn: &mut NN<T>, logs: &EpochLogs<T>) -> Result<Action> {
+        if self.writer.is_none() {
+            let mut writer = BufWriter::new(File::create(&self.path)?);
+            write!(writer, "epoch,loss")?;
+            if let Some(validation) = &logs.validation {
+                write!(writer, ",val_loss")?;
+                for (name, _) in validation.metrics.iter() {
+                    write!(writer, ",val_{}", name)?;
+                }
+            }
+            writeln!(writer)?;
+            self.writer = Some(writer);
+        }
+        let writer = self.writer.as_mut().unwrap();
+        write!(writer, "{},{}", logs.epoch, logs.loss)?;
+        if let Some(validation) = &logs.validation {
+            write!(writer, ",{}", validation.loss)?;
+            for (_, value) in validation.metrics.iter() {
+                write!(writer, ",{}", value)?;
+            }
+        }
+        writeln!(writer)?;
+        writer.flush()?;
+        Ok(Action::Continue)
+    }
+    fn on_train_end(&mut self, _nn: &mut NN<T>, _history: &History<T>) -> Result<()> {
+        self.writer = None;
+        Ok(())
+    }
+}
+
+/// Stops training as soon as a batch produces a NaN or infinite loss.
+#[derive(Default)]
+pub struct TerminateOnNaN {
+    terminated_at: Option<(u64, usize)>
+}
+
+impl TerminateOnNaN {
+    pub fn new() -> TerminateOnNaN {
+        TerminateOnNaN::default()
+    }
+
+    /// Epoch and batch of the non-finite loss, if training was terminated.
+    pub fn get_terminated_at(&self) -> Option<(u64, usize)> {
+        self.terminated_at
+    }
+}
+
+impl<T: Float> Callback<T> for TerminateOnNaN {
+    fn on_train_begin(&mut self, _nn: &mut NN<T>, _params: &TrainingParams) -> Result<()> {
+        self.terminated_at = None;
+        Ok(())
+    }
+    fn on_batch_end(&mut self, _nn: &mut NN<T>, logs: &BatchLogs<T>) -> Result<Action> {
+        if logs.loss.is_finite() {
+            return Ok(Action::Continue);
+        }
+        self.terminated_at = Some((logs.epoch, logs.batch));
+        Ok(Action::Stop)
+    }
+}
+
+#[cfg(test)]
+mod test_callbacks {
+    use super::*;
+    use crate::dataset::VecDataset;
+    use crate::layers::dense_layer::DenseLayer;
+    use crate::training::TrainConfig;
+
+    fn build(learning_rate: f64) -> NN {
+        let mut nn = NN::new([1, 2], learning_rate);
+        nn.add(Box::new(DenseLayer::new(1))).unwrap();
+        nn
+    }
+
+    fn dataset() -> VecDataset {
+        let inputs = (0..4).map(|i| Matrix::from_vec(vec![i as f64, 1.0], 1, 2).unwrap()).collect();
+        let targets = (0..4).map(|i| Matrix::from_vec(vec![2.0 * i as f64], 1, 1).unwrap()).collect();
+        VecDataset::new(inputs, targets).unwrap()
+    }
+
+    fn logs(epoch: u64, loss: f64) -> EpochLogs {
+        EpochLogs {epoch, loss, validation: None}
+    }
+
+    #[test]
+    fn test_early_stopping() {
+        let mut nn = build(0.0);
+        let mut early_stopping = EarlyStopping::new(3).monitor(Monitor::Loss);
+        let history = nn.fit_with_callbacks(&dataset(), &TrainConfig::new(100), &mut [&mut early_stopping]).unwrap();
+        assert_eq!(history.loss.len(), 4);
+        assert_eq!(early_stopping.get_stopped_epoch(), Some(3));
+
+        let mut early_stopping = EarlyStopping::new(1).monitor(Monitor::Loss).restore_best_weights(true);
+        let best = nn.get_weights();
+        assert_eq!(early_stopping.on_epoch_end(&mut nn, &logs(0, 1.0)).unwrap(), Action::Continue);
+        nn.set_weights(best.iter().map(|w| w + 1.0).collect()).unwrap();
+        assert_eq!(early_stopping.on_epoch_end(&mut nn, &logs(1, 2.0)).unwrap(), Action::Stop);
+        early_stopping.on_train_end(&mut nn, &History::default()).unwrap();
+        assert_eq!(nn.get_weights(), best);
+
+        let mut needs_validation: EarlyStopping = EarlyStopping::new(1);
+        assert!(needs_validation.on_epoch_end(&mut nn, &logs(0, 1.0)).is_err());
+    }
+
+    #[test]
+    fn test_terminate_on_nan() {
+        let mut nn = build(f64::NAN);
+        let mut terminate = TerminateOnNaN::new();
+        let history = nn.fit_with_callbacks(&dataset(), &TrainConfig::new(10), &mut [&mut terminate]).unwrap();
+        assert_eq!(terminate.get_terminated_at(), Some((0, 1)));
+        assert_eq!(history.loss.len(), 1);
+    }
+
+    #[test]
+    fn test_csv_logger_and_checkpoint() {
+        let directory = std::env::temp_dir().join(format!("nn_callbacks_{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let csv_path = directory.join("log.csv");
+        let checkpoint_path = directory.join("weights_{epoch}.json");
+        let mut nn = build(0.01);
+        let mut logger = CsvLogger::new(&csv_path);
+        let mut checkpoint = ModelCheckpoint::new(checkpoint_path.to_str().unwrap()).monitor(Monitor::Loss);
+        nn.fit_with_callbacks(&dataset(), &TrainConfig::new(3), &mut [&mut logger, &mut checkpoint]).unwrap();
+        let csv = std::fs::read_to_string(&csv_path).unwrap();
+        assert_eq!(csv.lines().next(), Some("epoch,loss"));
+        assert_eq!(csv.lines().count(), 4);
+        let mut restored = build(0.01);
+        restored.load_weights(directory.join("weights_2.json")).unwrap();
+        assert_eq!(restored.get_weights(), nn.get_weights());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,107 @@
+use crate::float::Float;
+use crate::matrix::{Matrix, Norm};
+
+/// Gradient clipping applied before each update. Clipping by value runs first,
+/// then per-parameter norm clipping, then global norm clipping across all layers.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct GradientClipping<T: Float = f64> {
+    value: Option<T>,
+    norm: Option<T>,
+    global_norm: Option<T>
+}
+
+impl<T: Float> GradientClipping<T> {
+    pub fn new() -> GradientClipping<T> {
+        GradientClipping {value: None, norm: None, global_norm: None}
+    }
+
+    /// Clamps every gradient element to `[-value, value]`.
+    pub fn by_value(mut self, value: T) -> GradientClipping<T> {
+        self.value = Some(value);
+        self
+    }
+
+    /// Rescales each parameter's gradient whose L2 norm exceeds `norm`.
+    pub fn by_norm(mut self, norm: T) -> GradientClipping<T> {
+        self.norm = Some(norm);
+        self
+    }
+
+    /// Rescales all gradients together when their combined L2 norm exceeds `global_norm`.
+    pub fn by_global_norm(mut self, global_norm: T) -> GradientClipping<T> {
+        self.global_norm = Some(global_norm);
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.value.is_some() || self.norm.is_some() || self.global_norm.is_some()
+    }
+
+    /// Clips `gradients` in place and returns their global norm before clipping.
+    pub fn clip(&self, gradients: &mut [&mut Matrix<T>]) -> T {
+        let pre_clip_norm = global_norm(gradients.iter().map(|gradient| &**gradient));
+        if let Some(value) = self.value {
+            for gradient in gradients.iter_mut() {
+                gradient.get_data_mut().iter_mut().for_each(|x| *x = x.max(-value).min(value));
+            }
+        }
+        if let Some(max_norm) = self.norm {
+            for gradient in gradients.iter_mut() {
+                scale_to_norm(gradient, gradient.norm(Norm::Frobenius), max_norm);
+            }
+        }
+        if let Some(max_norm) = self.global_norm {
+            let norm = global_norm(gradients.iter().map(|gradient| &**gradient));
+            for gradient in gradients.iter_mut() {
+                scale_to_norm(gradient, norm, max_norm);
+            }
+        }
+        pre_clip_norm
+    }
+}
+
+fn scale_to_norm<T: Float>(gradient: &mut Matrix<T>, norm: T, max_norm: T) {
+    if norm > max_norm {
+        *gradient *= max_norm / norm;
+    }
+}
+
+/// L2 norm of all elements of all gradients taken together.
+pub fn global_norm<'a, T: Float>(gradients: impl IntoIterator<Item = &'a Matrix<T>>) -> T {
+    gradients.into_iter().map(|gradient| gradient.get_data().iter().map(|&x| x * x).sum::<T>()).sum::<T>().sqrt()
+}
+
+#[cfg(test)]
+mod test_clipping {
+    use super::*;
+
+    fn gradients() -> Vec<Matrix> {
+        vec![Matrix::from_vec(vec![3.0, -4.0], 1, 2).unwrap(), Matrix::from_vec(vec![0.0, 12.0], 2, 1).unwrap()]
+    }
+
+    fn clip(clipping: GradientClipping) -> (f64, Vec<Matrix>) {
+        let mut gradients = gradients();
+        let norm = clipping.clip(&mut gradients.iter_mut().collect::<Vec<_>>());
+        (norm, gradients)
+    }
+
+    #[test]
+    fn test_clipping() {
+        let (norm, clipped) = clip(GradientClipping::new().by_value(3.5));
+        assert_eq!(norm, 13.0);
+        assert_eq!(clipped[0].get_data(), &vec![3.0, -3.5]);
+        assert_eq!(clipped[1].get_data(), &vec![0.0, 3.5]);
+
+        let (_, clipped) = clip(GradientClipping::new().by_norm(10.0));
+        assert_eq!(clipped[0], gradients()[0]);
+        assert_eq!(clipped[1].get_data(), &vec![0.0, 10.0]);
+
+        let (norm, clipped) = clip(GradientClipping::new().by_global_norm(6.5));
+        assert_eq!(norm, 13.0);
+        assert!((global_norm(&clipped) - 6.5).abs() < 1e-12);
+        assert_eq!(clipped[0].get_data(), &vec![1.5, -2.0]);
+
+        let (_, clipped) = clip(GradientClipping::new());
+        assert_eq!(clipped, gradients());
+    }
+}
@@ -0,0 +1,224 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::activation_function::{ActivationFunction, LeakyReLU, ReLU, Sigmoid, Tanh};
+use crate::error::{Error, Result};
+use crate::float::Float;
+use crate::initializers::Initializer;
+use crate::layers::activation_layer::ActivationLayer;
+use crate::layers::convolution_layer::ConvolutionalLayer;
+use crate::layers::dense_layer::DenseLayer;
+use crate::layers::flatten_layer::FlattenLayer;
+use crate::layers::layer_interface::Layer;
+use crate::neural_network::NN;
+use crate::regularization::{Constraint, Regularizer};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivationConfig {
+    Sigmoid,
+    Relu,
+    LeakyRelu {alpha: f64},
+    Tanh
+}
+
+impl ActivationConfig {
+    pub fn build<T: Float>(&self) -> Result<Box<dyn ActivationFunction<T>>> {
+        Ok(match *self {
+            ActivationConfig::Sigmoid => Box::new(Sigmoid),
+            ActivationConfig::Relu => Box::new(ReLU),
+            ActivationConfig::LeakyRelu {alpha} => {
+                if !alpha.is_finite() {
+                    return Err(Error::InvalidConfiguration(format!("LeakyReLU alpha must be finite, got {}", alpha)));
+                }
+                Box::new(LeakyReLU::new(T::from_f64(alpha)))
+            }
+            ActivationConfig::Tanh => Box::new(Tanh)
+        })
+    }
+}
+
+fn default_stride() -> usize {
+    1
+}
+
+/// One layer of a `ModelConfig`, tagged by `"type"` in the file.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum LayerConfig {
+    Dense {
+        units: usize,
+        #[serde(default)]
+        kernel_initializer: Initializer,
+        #[serde(default)]
+        bias_initializer: Initializer,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        kernel_regularizer: Option<Regularizer>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        bias_regularizer: Option<Regularizer>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        kernel_constraint: Option<Constraint>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        bias_constraint: Option<Constraint>
+    },
+    Convolutional {
+        kernel_size: [usize; 2],
+        #[serde(default = "default_stride")]
+        stride: usize,
+        #[serde(default)]
+        padding: usize,
+        #[serde(default)]
+        initializer: Initializer,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        kernel_regularizer: Option<Regularizer>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        kernel_constraint: Option<Constraint>
+    },
+    Flatten,
+    Activation {activation: ActivationConfig}
+}
+
+impl LayerConfig {
+    /// The `"type"` tag of the layer.
+    pub fn get_type(&self) -> &'static str {
+        match self {
+            LayerConfig::Dense {..} => "dense",
+            LayerConfig::Convolutional {..} => "convolutional",
+            LayerConfig::Flatten => "flatten",
+            LayerConfig::Activation {..} => "activation"
+        }
+    }
+
+    /// Builds the uninitialized layer; `NN::add` initializes it with its input size.
+    pub fn build<T: Float>(&self) -> Result<Box<dyn Layer<T>>> {
+        Ok(match *self {
+            LayerConfig::Dense {units, kernel_initializer, bias_initializer, kernel_regularizer, bias_regularizer,
+                                kernel_constraint, bias_constraint} => {
+                if units == 0 {
+                    return Err(Error::InvalidConfiguration("units must be positive".to_string()));
+                }
+                let mut layer = DenseLayer::new(units).kernel_initializer(kernel_initializer).bias_initializer(bias_initializer);
+                if let Some(regularizer) = kernel_regularizer {
+                    layer = layer.kernel_regularizer(regularizer);
+                }
+                if let Some(regularizer) = bias_regularizer {
+                    layer = layer.bias_regularizer(regularizer);
+                }
+                if let Some(constraint) = kernel_constraint {
+                    layer = layer.kernel_constraint(constraint);
+                }
+                if let Some(constraint) = bias_constraint {
+                    layer = layer.bias_constraint(constraint);
+                }
+                Box::new(layer)
+            }
+            LayerConfig::Convolutional {kernel_size, stride, padding, initializer, kernel_regularizer, kernel_constraint} => {
+                if kernel_size[0] == 0 || kernel_size[1] == 0 {
+                    return Err(Error::InvalidConfiguration(
+                        format!("kernel_size must be positive, got [{}, {}]", kernel_size[0], kernel_size[1])));
+                }
+                if stride == 0 {
+                    return Err(Error::InvalidConfiguration("stride must be positive".to_string()));
+                }
+                let mut layer = ConvolutionalLayer::from_initializer(kernel_size, stride, padding, initializer);
+                if let Some(regularizer) = kernel_regularizer {
+                    layer = layer.kernel_regularizer(regularizer);
+                }
+                if let Some(constraint) = kernel_constraint {
+                    layer = layer.kernel_constraint(constraint);
+                }
+                Box::new(layer)
+            }
+            LayerConfig::Flatten => Box::new(FlattenLayer::new()),
+            LayerConfig::Activation {activation} => Box::new(ActivationLayer::new(activation.build()?))
+        })
+    }
+}
+
+/// Architecture and training setup of a network, stored as JSON. `NN::save` writes
+/// it together with the weights into one file that `NN::load` reads back.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ModelConfig {
+    pub input_size: [usize; 2],
+    pub layers: Vec<LayerConfig>,
+    pub learning_rate: f64
+}
+
+impl ModelConfig {
+    pub fn from_json(json: &str) -> Result<ModelConfig> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<ModelConfig> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+
+    /// Builds a freshly initialized network, validated like `NNBuilder::build`.
+    pub fn build<T: Float>(&self) -> Result<NN<T>> {
+        let builder = NN::builder(self.input_size).learning_rate(T::from_f64(self.learning_rate));
+        self.layers.iter().fold(builder, |builder, layer| builder.config(layer.clone())).build()
+    }
+}
+
+#[cfg(test)]
+mod test_config {
+    use super::*;
+    use crate::matrix::Matrix;
+
+    const CONFIG: &str = r#"{
+        "input_size": [4, 4],
+        "layers": [
+            {"type": "convolutional", "kernel_size": [2, 2], "initializer": {"type": "he_uniform"}},
+            {"type": "activation", "activation": "relu"},
+            {"type": "flatten"},
+            {"type": "dense", "units": 3, "kernel_regularizer": {"l2": 0.01}},
+            {"type": "activation", "activation": {"leaky_relu": {"alpha": 0.1}}}
+        ],
+        "learning_rate": 0.05
+    }"#;
+
+    #[test]
+    fn test_build() {
+        let config = ModelConfig::from_json(CONFIG).unwrap();
+        let nn: NN = config.build().unwrap();
+        let names: Vec<String> = nn.get_summary().layers.into_iter().map(|layer| layer.name).collect();
+        assert_eq!(names, ["convolutional", "activation(relu)", "flatten", "dense", "activation(leaky_relu)"]);
+        assert_eq!(nn.predict(&Matrix::new(4, 4)).unwrap().get_shape(), [1, 3]);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let config = ModelConfig::from_json(CONFIG).unwrap();
+        let nn: NN = config.build().unwrap();
+        let exported = nn.get_config().unwrap();
+        assert_eq!(exported, config);
+        assert_eq!(ModelConfig::from_json(&exported.to_json().unwrap()).unwrap(), config);
+    }
+
+    #[test]
+    fn test_invalid_layer() {
+        let mut config = ModelConfig::from_json(CONFIG).unwrap();
+        config.layers.remove(2);
+        let error = config.build::<f64>().err().unwrap().to_string();
+        assert!(error.contains("layer 2 (dense)"), "{}", error);
+        config.layers[0] = LayerConfig::Convolutional {kernel_size: [0, 2], stride: 1, padding: 0,
+            initializer: Initializer::default(), kernel_regularizer: None, kernel_constraint: None};
+        let error = config.build::<f64>().err().unwrap().to_string();
+        assert!(error.contains("layer 0 (convolutional): kernel_size must be positive"), "{}", error);
+        let unknown = CONFIG.replace("\"flatten\"", "\"pooling\"");
+        assert!(matches!(ModelConfig::from_json(&unknown), Err(Error::Format(_))));
+    }
+}
@@ -0,0 +1,165 @@
+use std::marker::PhantomData;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use crate::dataset::Dataset;
+use crate::error::{Error, Result};
+use crate::float::Float;
+use crate::matrix::Matrix;
+
+#[derive(Clone, Debug)]
+pub struct Batch<T: Float = f64> {
+    pub inputs: Vec<Matrix<T>>,
+    pub targets: Vec<Matrix<T>>
+}
+
+impl<T: Float> Batch<T> {
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+}
+
+/// Splits a dataset into batches, optionally reshuffling the sample order on every epoch.
+pub struct DataLoader<'a, T: Float, D: Dataset<T> + ?Sized> {
+    dataset: &'a D,
+    batch_size: usize,
+    shuffle: bool,
+    drop_last: bool,
+    rng: StdRng,
+    element_type: PhantomData<T>
+}
+
+impl<'a, T: Float, D: Dataset<T> + ?Sized> DataLoader<'a, T, D> {
+    pub fn new(dataset: &'a D, batch_size: usize) -> Result<DataLoader<'a, T, D>> {
+        if batch_size == 0 {
+            return Err(Error::InvalidConfiguration("Batch size must be positive".to_string()));
+        }
+        Ok(DataLoader {dataset, batch_size, shuffle: false, drop_last: false, rng: StdRng::from_entropy(), element_type: PhantomData})
+    }
+
+    pub fn shuffle(mut self, shuffle: bool) -> DataLoader<'a, T, D> {
+        self.shuffle = shuffle;
+        self
+    }
+
+    /// Skips the final batch of an epoch when it is smaller than `batch_size`.
+    pub fn drop_last(mut self, drop_last: bool) -> DataLoader<'a, T, D> {
+        self.drop_last = drop_last;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> DataLoader<'a, T, D> {
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    pub fn get_dataset(&self) -> &'a D {
+        self.dataset
+    }
+
+    pub fn get_batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    pub fn num_batches(&self) -> usize {
+        if self.drop_last {
+            self.dataset.len() / self.batch_size
+        } else {
+            self.dataset.len().div_ceil(self.batch_size)
+        }
+    }
+
+    /// Starts a new epoch, reshuffling first if enabled.
+    pub fn iter(&mut self) -> Batches<'_, 'a, T, D> {
+        let mut order: Vec<usize> = (0..self.dataset.len()).collect();
+        if self.shuffle {
+            order.shuffle(&mut self.rng);
+        }
+        let num_batches = self.num_batches();
+        Batches {loader: self, order, batch: 0, num_batches}
+    }
+}
+
+pub struct Batches<'l, 'a, T: Float, D: Dataset<T> + ?Sized> {
+    loader: &'l DataLoader<'a, T, D>,
+    order: Vec<usize>,
+    batch: usize,
+    num_batches: usize
+}
+
+impl<'l, 'a, T: Float, D: Dataset<T> + ?Sized> Iterator for Batches<'l, 'a, T, D> {
+    type Item = Result<Batch<T>>;
+
+    fn next(&mut self) -> Option<Result<Batch<T>>> {
+        if self.batch >= self.num_batches {
+            return None;
+        }
+        let start = self.batch * self.loader.batch_size;
+        let end = (start + self.loader.batch_size).min(self.order.len());
+        self.batch += 1;
+        let mut batch = Batch {inputs: Vec::with_capacity(end - start), targets: Vec::with_capacity(end - start)};
+        for &index in &self.order[start..end] {
+            match self.loader.dataset.get(index) {
+                Ok((input, target)) => {
+                    batch.inputs.push(input);
+                    batch.targets.push(target);
+                },
+                Err(error) => return Some(Err(error))
+            }
+        }
+        Some(Ok(batch))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.num_batches - self.batch;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'l, 'a, T: Float, D: Dataset<T> + ?Sized> ExactSizeIterator for Batches<'l, 'a, T, D> {}
+
+#[cfg(test)]
+mod test_data_loader {
+    use super::*;
+    use crate::dataset::VecDataset;
+
+    fn dataset() -> VecDataset {
+        let inputs = (0..10).map(|i| Matrix::from_vec(vec![i as f64], 1, 1).unwrap()).collect();
+        let targets = (0..10).map(|i| Matrix::from_vec(vec![-(i as f64)], 1, 1).unwrap()).collect();
+        VecDataset::new(inputs, targets).unwrap()
+    }
+
+    fn epoch(loader: &mut DataLoader<f64, VecDataset>) -> Vec<Vec<f64>> {
+        loader.iter().map(|batch| batch.unwrap().inputs.iter().map(|m| m.get(0, 0)).collect()).collect()
+    }
+
+    #[test]
+    fn test_batching() {
+        let dataset = dataset();
+        let mut loader = DataLoader::new(&dataset, 4).unwrap();
+        assert_eq!(epoch(&mut loader), vec![vec![0.0, 1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0, 7.0], vec![8.0, 9.0]]);
+        let mut loader = DataLoader::new(&dataset, 4).unwrap().drop_last(true);
+        assert_eq!(loader.num_batches(), 2);
+        assert_eq!(epoch(&mut loader).len(), 2);
+        assert!(DataLoader::<f64, VecDataset>::new(&dataset, 0).is_err());
+    }
+
+    #[test]
+    fn test_seeded_shuffle() {
+        let dataset = dataset();
+        let mut first = DataLoader::new(&dataset, 3).unwrap().shuffle(true).seed(7);
+        let mut second = DataLoader::new(&dataset, 3).unwrap().shuffle(true).seed(7);
+        let epoch_1 = epoch(&mut first);
+        assert_eq!(epoch_1, epoch(&mut second));
+        assert_ne!(epoch_1, epoch(&mut first));
+        let mut seen: Vec<f64> = epoch_1.concat();
+        seen.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(seen, (0..10).map(|i| i as f64).collect::<Vec<_>>());
+        let batch = first.iter().next().unwrap().unwrap();
+        assert_eq!(batch.inputs[0].get(0, 0), -batch.targets[0].get(0, 0));
+    }
+}
@@ -0,0 +1,177 @@
+use std::fs;
+use std::path::Path;
+use crate::error::{Error, Result};
+use crate::float::Float;
+use crate::matrix::Matrix;
+
+/// Indexed collection of `(input, target)` pairs.
+pub trait Dataset<T: Float = f64> {
+    fn len(&self) -> usize;
+    fn get(&self, index: usize) -> Result<(Matrix<T>, Matrix<T>)>;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: Float, D: Dataset<T> + ?Sized> Dataset<T> for &D {
+    fn len(&self) -> usize {
+        (**self).len()
+    }
+    fn get(&self, index: usize) -> Result<(Matrix<T>, Matrix<T>)> {
+        (**self).get(index)
+    }
+}
+
+pub(crate) fn check_index(index: usize, len: usize) -> Result<()> {
+    if index >= len {
+        return Err(Error::InvalidConfiguration(format!("Index {} out of bounds for dataset of length {}", index, len)));
+    }
+    Ok(())
+}
+
+/// Encodes `label` as a `[1, num_classes]` row with a single one.
+pub fn one_hot<T: Float>(label: usize, num_classes: usize) -> Result<Matrix<T>> {
+    if label >= num_classes {
+        return Err(Error::InvalidConfiguration(format!("Label {} out of range for {} classes", label, num_classes)));
+    }
+    let mut target = Matrix::new(1, num_classes);
+    target[(0, label)] = T::one();
+    Ok(target)
+}
+
+/// In-memory dataset built from matching vectors of inputs and targets.
+#[derive(Clone, Debug)]
+pub struct VecDataset<T: Float = f64> {
+    inputs: Vec<Matrix<T>>,
+    targets: Vec<Matrix<T>>
+}
+
+impl<T: Float> VecDataset<T> {
+    pub fn new(inputs: Vec<Matrix<T>>, targets: Vec<Matrix<T>>) -> Result<VecDataset<T>> {
+        if inputs.len() != targets.len() {
+            return Err(Error::InvalidConfiguration(
+                format!("Number of inputs ({}) and targets ({}) must match", inputs.len(), targets.len())));
+        }
+        Ok(VecDataset {inputs, targets})
+    }
+
+    pub fn get_inputs(&self) -> &Vec<Matrix<T>> {
+        &self.inputs
+    }
+
+    pub fn get_targets(&self) -> &Vec<Matrix<T>> {
+        &self.targets
+    }
+}
+
+impl<T: Float> Dataset<T> for VecDataset<T> {
+    fn len(&self) -> usize {
+        self.inputs.len()
+    }
+    fn get(&self, index: usize) -> Result<(Matrix<T>, Matrix<T>)> {
+        check_index(index, self.len())?;
+        Ok((self.inputs[index].clone(), self.targets[index].clone()))
+    }
+}
+
+/// Rows of numbers from a comma-separated file. Blank lines are ignored and a first
+/// line that is not numeric is skipped as a header.
+pub fn read_csv<T: Float>(path: impl AsRef<Path>) -> Result<Vec<Vec<T>>> {
+    let text = fs::read_to_string(path)?;
+    let mut rows = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let row: std::result::Result<Vec<f64>, _> = line.split(',').map(|field| field.trim().parse::<f64>()).collect();
+        match row {
+            Ok(row) => rows.push(row.into_iter().map(T::from_f64).collect()),
+            Err(_) if index == 0 => continue,
+            Err(error) => return Err(Error::Format(format!("line {}: {}", index + 1, error)))
+        }
+    }
+    Ok(rows)
+}
+
+/// Reads a CSV file with one sample per line: the input values in row-major order
+/// followed by `target_len` target values.
+pub fn load_csv<T: Float>(path: impl AsRef<Path>, input_size: [usize; 2], target_len: usize) -> Result<VecDataset<T>> {
+    let input_len = input_size[0] * input_size[1];
+    let mut inputs = Vec::new();
+    let mut targets = Vec::new();
+    for (index, mut row) in read_csv(path)?.into_iter().enumerate() {
+        if row.len() != input_len + target_len {
+            return Err(Error::Format(
+                format!("sample {}: expected {} values, got {}", index + 1, input_len + target_len, row.len())));
+        }
+        let target = row.split_off(input_len);
+        inputs.push(Matrix::from_vec(row, input_size[0], input_size[1])?);
+        targets.push(Matrix::from_vec(target, 1, target_len)?);
+    }
+    VecDataset::new(inputs, targets)
+}
+
+/// View of a dataset restricted to the given indices.
+pub struct Subset<'a, D: ?Sized> {
+    dataset: &'a D,
+    indices: Vec<usize>
+}
+
+impl<'a, D: ?Sized> Subset<'a, D> {
+    pub fn new<T: Float>(dataset: &'a D, indices: Vec<usize>) -> Result<Subset<'a, D>> where D: Dataset<T> {
+        if let Some(&index) = indices.iter().find(|&&index| index >= dataset.len()) {
+            check_index(index, dataset.len())?;
+        }
+        Ok(Subset {dataset, indices})
+    }
+
+    pub fn get_indices(&self) -> &Vec<usize> {
+        &self.indices
+    }
+}
+
+impl<'a, T: Float, D: Dataset<T> + ?Sized> Dataset<T> for Subset<'a, D> {
+    fn len(&self) -> usize {
+        self.indices.len()
+    }
+    fn get(&self, index: usize) -> Result<(Matrix<T>, Matrix<T>)> {
+        check_index(index, self.len())?;
+        self.dataset.get(self.indices[index])
+    }
+}
+
+#[cfg(test)]
+mod test_dataset {
+    use super::*;
+
+    #[test]
+    fn test_vec_dataset() {
+        let inputs = vec![Matrix::new(1, 2), Matrix::new(1, 2)];
+        let targets: Vec<Matrix> = vec![one_hot(0, 3).unwrap(), one_hot(2, 3).unwrap()];
+        let dataset = VecDataset::new(inputs.clone(), targets).unwrap();
+        assert_eq!(dataset.len(), 2);
+        assert_eq!(dataset.get(1).unwrap().1.get_data(), &vec![0.0, 0.0, 1.0]);
+        assert!(dataset.get(2).is_err());
+        assert!(VecDataset::new(inputs, vec![Matrix::new(1, 1)]).is_err());
+        assert!(one_hot::<f64>(3, 3).is_err());
+        let subset = Subset::new(&dataset, vec![1]).unwrap();
+        assert_eq!(subset.len(), 1);
+        assert_eq!(subset.get(0).unwrap().1, dataset.get(1).unwrap().1);
+        assert!(Subset::new(&dataset, vec![0, 2]).is_err());
+    }
+
+    #[test]
+    fn test_load_csv() {
+        let path = std::env::temp_dir().join(format!("nn_dataset_{}.csv", std::process::id()));
+        std::fs::write(&path, "x1,x2,x3,x4,y\n0.5, 1, 2, 3, 1\n\n-1,0,0,0,0\n").unwrap();
+        let dataset: VecDataset = load_csv(&path, [2, 2], 1).unwrap();
+        assert_eq!(dataset.len(), 2);
+        assert_eq!(dataset.get_inputs()[0], Matrix::from_vec(vec![0.5, 1.0, 2.0, 3.0], 2, 2).unwrap());
+        assert_eq!(dataset.get_targets()[1].get_data(), &vec![0.0]);
+        assert!(matches!(load_csv::<f64>(&path, [1, 2], 1), Err(Error::Format(_))));
+        std::fs::write(&path, "1,2\n3,x\n").unwrap();
+        let error = read_csv::<f64>(&path).err().unwrap();
+        assert!(error.to_string().contains("line 2"), "{}", error);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
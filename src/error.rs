@@ -0,0 +1,60 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum Error {
+    ShapeMismatch {
+        operation: &'static str,
+        left: [usize; 2],
+        right: [usize; 2]
+    },
+    InvalidConfiguration(String),
+    Numerical(String),
+    Io(std::io::Error),
+    Format(String)
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    pub(crate) fn shape_mismatch(operation: &'static str, left: [usize; 2], right: [usize; 2]) -> Error {
+        Error::ShapeMismatch {operation, left, right}
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ShapeMismatch {operation, left, right} => write!(f,
+                "Matrix dimensions must match for {}: [{}, {}] and [{}, {}]", operation, left[0], left[1], right[0], right[1]),
+            Error::InvalidConfiguration(message) => write!(f, "Invalid configuration: {}", message),
+            Error::Numerical(message) => write!(f, "Numerical error: {}", message),
+            Error::Io(error) => write!(f, "I/O error: {}", error),
+            Error::Format(message) => write!(f, "Format error: {}", message)
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Error {
+        Error::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Error {
+        if error.is_io() {
+            Error::Io(error.into())
+        } else {
+            Error::Format(error.to_string())
+        }
+    }
+}
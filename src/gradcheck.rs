@@ -0,0 +1,223 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use crate::error::{Error, Result};
+use crate::float::Float;
+use crate::layers::layer_interface::{check_gradients, Layer};
+use crate::matrix::Matrix;
+use crate::neural_network::NN;
+
+/// Maximum relative error between the gradients from `backward` and central
+/// finite differences, for the input and for every parameter tensor in `parameters()` order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GradCheckReport {
+    pub input: f64,
+    pub parameters: Vec<f64>
+}
+
+impl GradCheckReport {
+    pub fn max_error(&self) -> f64 {
+        self.parameters.iter().fold(self.input, |max, &error| max.max(error))
+    }
+}
+
+fn relative_error(analytical: f64, numerical: f64) -> f64 {
+    (analytical - numerical).abs() / analytical.abs().max(numerical.abs()).max(1e-8)
+}
+
+fn max_relative_error<T: Float>(analytical: &Matrix<T>, numerical: &[f64]) -> f64 {
+    analytical.get_data().iter().zip(numerical.iter())
+        .fold(0.0, |max, (&a, &n)| max.max(relative_error(a.to_f64(), n)))
+}
+
+/// Input error and parameter gradients from a single `backward` call.
+fn analytical_gradients<T: Float>(layer: &mut dyn Layer<T>, output_error: &Matrix<T>) -> Result<(Matrix<T>, Vec<Matrix<T>>)> {
+    layer.zero_grad();
+    let input_error = layer.backward(output_error)?;
+    let gradients = layer.gradients().into_iter().cloned().collect();
+    layer.zero_grad();
+    Ok((input_error, gradients))
+}
+
+/// Central differences of `objective` with respect to every element of the matrix
+/// returned by `target`, which is restored afterwards.
+fn numerical_gradient<S, T: Float>(state: &mut S, epsilon: f64, target: impl Fn(&mut S) -> &mut Matrix<T>,
+                                   objective: impl Fn(&mut S) -> Result<T>) -> Result<Vec<f64>> {
+    let len = target(state).get_data().len();
+    let mut gradient = Vec::with_capacity(len);
+    for i in 0..len {
+        let original = target(state).get_data()[i];
+        target(state).get_data_mut()[i] = original + T::from_f64(epsilon);
+        let plus = objective(state)?.to_f64();
+        target(state).get_data_mut()[i] = original - T::from_f64(epsilon);
+        let minus = objective(state)?.to_f64();
+        target(state).get_data_mut()[i] = original;
+        gradient.push((plus - minus) / (2.0 * epsilon));
+    }
+    Ok(gradient)
+}
+
+fn random_like<T: Float>(shape: [usize; 2], rng: &mut StdRng) -> Result<Matrix<T>> {
+    let data = (0..shape[0] * shape[1]).map(|_| T::from_f64(rng.gen_range(-1.0..1.0))).collect();
+    Matrix::from_vec(data, shape[0], shape[1])
+}
+
+/// Checks `layer` on `input` against the scalar objective `sum(output * R)` plus the
+/// layer's regularization loss, where `R` is a fixed random matrix. Fails for layers
+/// that only implement `backwards`, which compute no gradients.
+pub fn check_layer<T: Float>(layer: &mut dyn Layer<T>, input: &Matrix<T>, epsilon: f64) -> Result<GradCheckReport> {
+    check_gradients(layer, 0, "check_layer")?;
+    let output = layer.forward(input)?;
+    let projection = random_like(output.get_shape(), &mut StdRng::seed_from_u64(0))?;
+    let (input_error, parameter_gradients) = analytical_gradients(layer, &projection)?;
+    if input_error.get_shape() != input.get_shape() {
+        return Err(Error::shape_mismatch("check_layer", input.get_shape(), input_error.get_shape()));
+    }
+    let objective = |layer: &mut dyn Layer<T>, input: &Matrix<T>| -> Result<T> {
+        let output = layer.forward(input)?;
+        let projected: T = output.get_data().iter().zip(projection.get_data().iter()).map(|(&o, &r)| o * r).sum();
+        Ok(projected + layer.regularization_loss())
+    };
+
+    let mut state = (layer, input.clone());
+    let numerical = numerical_gradient(&mut state, epsilon, |(_, input)| input, |(layer, input)| objective(*layer, input))?;
+    let input_report = max_relative_error(&input_error, &numerical);
+    let mut parameter_reports = Vec::with_capacity(parameter_gradients.len());
+    for (index, analytical) in parameter_gradients.iter().enumerate() {
+        let numerical = numerical_gradient(&mut state, epsilon,
+            |(layer, _)| layer.parameters_mut().into_iter().nth(index).unwrap(),
+            |(layer, input)| objective(*layer, input))?;
+        parameter_reports.push(max_relative_error(analytical, &numerical));
+    }
+    state.0.forward(input)?;
+    Ok(GradCheckReport {input: input_report, parameters: parameter_reports})
+}
+
+/// Checks a whole network on one sample. `loss` returns the loss of a prediction
+/// and its gradient with respect to that prediction; the network's regularization
+/// loss is added to it. Parameters are reported across all layers in order.
+pub fn check_network<T: Float>(nn: &mut NN<T>, input: &Matrix<T>, epsilon: f64,
+                               loss: impl Fn(&Matrix<T>) -> Result<(T, Matrix<T>)>) -> Result<GradCheckReport> {
+    for (index, layer) in nn.get_layers_mut().iter().enumerate() {
+        check_gradients(layer.as_ref(), index, "check_network")?;
+    }
+    let output = nn.forward(input)?;
+    let (_, mut error) = loss(&output)?;
+    let mut parameter_gradients = Vec::new();
+    for layer in nn.get_layers_mut().iter_mut().rev() {
+        let (input_error, gradients) = analytical_gradients(layer.as_mut(), &error)?;
+        parameter_gradients.splice(0..0, gradients);
+        error = input_error;
+    }
+    let objective = |nn: &mut NN<T>, input: &Matrix<T>| -> Result<T> {
+        Ok(loss(&nn.forward(input)?)?.0 + nn.regularization_loss())
+    };
+
+    let mut state = (nn, input.clone());
+    let numerical = numerical_gradient(&mut state, epsilon, |(_, input)| input, |(nn, input)| objective(nn, input))?;
+    let input_report = max_relative_error(&error, &numerical);
+    let mut parameter_reports = Vec::with_capacity(parameter_gradients.len());
+    for (index, analytical) in parameter_gradients.iter().enumerate() {
+        let numerical = numerical_gradient(&mut state, epsilon,
+            |(nn, _)| nn.get_layers_mut().iter_mut().flat_map(|layer| layer.parameters_mut()).nth(index).unwrap(),
+            |(nn, input)| objective(nn, input))?;
+        parameter_reports.push(max_relative_error(analytical, &numerical));
+    }
+    Ok(GradCheckReport {input: input_report, parameters: parameter_reports})
+}
+
+/// Sum of squared errors against `target`, for use with `check_network`.
+pub fn squared_error<T: Float>(target: &Matrix<T>) -> impl Fn(&Matrix<T>) -> Result<(T, Matrix<T>)> + '_ {
+    move |prediction| {
+        if prediction.get_shape() != target.get_shape() {
+            return Err(Error::shape_mismatch("squared_error", target.get_shape(), prediction.get_shape()));
+        }
+        let difference = prediction - target;
+        let loss = difference.get_data().iter().map(|&x| x * x).sum();
+        Ok((loss, difference * T::from_f64(2.0)))
+    }
+}
+
+#[cfg(test)]
+mod test_gradcheck {
+    use super::*;
+    use crate::activation_function::Tanh;
+    use crate::layers::{activation_layer::ActivationLayer, dense_layer::DenseLayer};
+    use crate::regularization::Regularizer;
+
+    #[test]
+    fn test_network() {
+        let mut nn = NN::new([1, 3], 0.1);
+        nn.add(Box::new(DenseLayer::new(4).kernel_regularizer(Regularizer::L2(0.1)))).unwrap();
+        nn.add(Box::new(ActivationLayer::new(Box::new(Tanh{})))).unwrap();
+        nn.add(Box::new(DenseLayer::new(2))).unwrap();
+        let input = Matrix::from_vec(vec![0.3, -0.7, 0.2], 1, 3).unwrap();
+        let target = Matrix::from_vec(vec![1.0, -1.0], 1, 2).unwrap();
+        let report = check_network(&mut nn, &input, 1e-6, squared_error(&target)).unwrap();
+        assert_eq!(report.parameters.len(), 4);
+        assert!(report.max_error() < 1e-6, "{:?}", report);
+    }
+
+    struct BrokenLayer(Matrix, Matrix);
+
+    impl Layer for BrokenLayer {
+        fn forward(&mut self, inputs: &Matrix) -> Result<Matrix> {
+            Ok(inputs * self.0.get(0, 0))
+        }
+        // forgets both the scale in the input error and the parameter gradient
+        fn backward(&mut self, output_error: &Matrix) -> Result<Matrix> {
+            Ok(output_error.clone())
+        }
+        fn get_size(&self) -> [usize; 2] {
+            [1, 2]
+        }
+        fn parameters(&self) -> Vec<&Matrix> {
+            vec![&self.0]
+        }
+        fn parameters_mut(&mut self) -> Vec<&mut Matrix> {
+            vec![&mut self.0]
+        }
+        fn gradients(&self) -> Vec<&Matrix> {
+            vec![&self.1]
+        }
+        fn gradients_mut(&mut self) -> Vec<&mut Matrix> {
+            vec![&mut self.1]
+        }
+    }
+
+    #[test]
+    fn test_detects_wrong_gradients() {
+        let mut layer = BrokenLayer(Matrix::from_vec(vec![3.0], 1, 1).unwrap(), Matrix::new(1, 1));
+        let report = check_layer(&mut layer, &Matrix::from_vec(vec![0.5, 1.0], 1, 2).unwrap(), 1e-6).unwrap();
+        assert!(report.input > 0.5);
+        assert!(report.parameters[0] > 0.5);
+    }
+
+    struct Scale(f64);
+
+    impl Layer for Scale {
+        fn forward(&mut self, inputs: &Matrix) -> Result<Matrix> {
+            Ok(inputs * self.0)
+        }
+        fn backwards(&mut self, output_error: &Matrix, learning_rate: f64) -> Result<Matrix> {
+            self.0 -= learning_rate;
+            Ok(output_error * self.0)
+        }
+        fn get_size(&self) -> [usize; 2] {
+            [1, 2]
+        }
+    }
+
+    #[test]
+    fn test_rejects_layers_without_gradients() {
+        let input = Matrix::from_vec(vec![0.5, 1.0], 1, 2).unwrap();
+        let mut layer = Scale(3.0);
+        assert!(matches!(check_layer(&mut layer, &input, 1e-6), Err(Error::InvalidConfiguration(_))));
+        assert_eq!(layer.0, 3.0);
+
+        let mut nn = NN::new([1, 2], 0.1);
+        nn.add(Box::new(DenseLayer::new(2))).unwrap();
+        nn.add(Box::new(Scale(3.0))).unwrap();
+        let target = Matrix::from_vec(vec![1.0, -1.0], 1, 2).unwrap();
+        assert!(matches!(check_network(&mut nn, &input, 1e-6, squared_error(&target)), Err(Error::InvalidConfiguration(_))));
+    }
+}
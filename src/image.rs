@@ -0,0 +1,90 @@
+use std::fs;
+use std::path::Path;
+use crate::error::{Error, Result};
+use crate::float::Float;
+use crate::matrix::Matrix;
+
+// next whitespace-separated header token, skipping `#` comments
+fn next_token<'a>(bytes: &'a [u8], position: &mut usize) -> Result<&'a [u8]> {
+    loop {
+        while *position < bytes.len() && bytes[*position].is_ascii_whitespace() {
+            *position += 1;
+        }
+        if *position < bytes.len() && bytes[*position] == b'#' {
+            while *position < bytes.len() && bytes[*position] != b'\n' {
+                *position += 1;
+            }
+            continue;
+        }
+        break;
+    }
+    let start = *position;
+    while *position < bytes.len() && !bytes[*position].is_ascii_whitespace() {
+        *position += 1;
+    }
+    if start == *position {
+        return Err(Error::Format("PGM header is truncated".to_string()));
+    }
+    Ok(&bytes[start..*position])
+}
+
+fn next_number(bytes: &[u8], position: &mut usize) -> Result<usize> {
+    let token = next_token(bytes, position)?;
+    std::str::from_utf8(token).ok().and_then(|token| token.parse().ok())
+        .ok_or_else(|| Error::Format(format!("Invalid number in PGM file: {}", String::from_utf8_lossy(token))))
+}
+
+/// Parses a grayscale PGM image (binary `P5` or plain `P2`) into a `[height, width]`
+/// matrix. Pixels are divided by `maxval + 1`, the scaling of the MNIST loader.
+pub fn parse_pgm<T: Float>(bytes: &[u8]) -> Result<Matrix<T>> {
+    let mut position = 0;
+    let binary = match next_token(bytes, &mut position)? {
+        b"P5" => true,
+        b"P2" => false,
+        _ => return Err(Error::Format("Only grayscale PGM images (P2 or P5) are supported".to_string()))
+    };
+    let width = next_number(bytes, &mut position)?;
+    let height = next_number(bytes, &mut position)?;
+    let max_value = next_number(bytes, &mut position)?;
+    if max_value == 0 || max_value > 65535 {
+        return Err(Error::Format(format!("Invalid PGM maximum value {}", max_value)));
+    }
+    let overflow = || Error::Format("PGM header sizes overflow".to_string());
+    let len = width.checked_mul(height).ok_or_else(overflow)?;
+    let pixels: Vec<usize> = if binary {
+        // a single whitespace byte separates the header from the raster
+        let start = position + 1;
+        let bytes_per_pixel = if max_value < 256 {1} else {2};
+        let end = len.checked_mul(bytes_per_pixel).and_then(|size| size.checked_add(start)).ok_or_else(overflow)?;
+        let raster = bytes.get(start..end)
+            .ok_or_else(|| Error::Format("PGM data is truncated".to_string()))?;
+        raster.chunks(bytes_per_pixel).map(|pixel| pixel.iter().fold(0, |value, &byte| value * 256 + byte as usize)).collect()
+    } else {
+        (0..len).map(|_| next_number(bytes, &mut position)).collect::<Result<_>>()?
+    };
+    let scale = (max_value + 1) as f64;
+    Matrix::from_vec(pixels.into_iter().map(|pixel| T::from_f64(pixel as f64 / scale)).collect(), height, width)
+}
+
+pub fn load_pgm<T: Float>(path: impl AsRef<Path>) -> Result<Matrix<T>> {
+    parse_pgm(&fs::read(path)?)
+}
+
+#[cfg(test)]
+mod test_image {
+    use super::*;
+
+    #[test]
+    fn test_parse_pgm() {
+        let plain: Matrix = parse_pgm(b"P2\n# comment\n3 2\n255\n0 64 128\n255 0 32\n").unwrap();
+        assert_eq!(plain.get_shape(), [2, 3]);
+        assert_eq!(plain.get(0, 1), 0.25);
+        let mut binary = b"P5 3 2 255\n".to_vec();
+        binary.extend([0, 64, 128, 255, 0, 32]);
+        assert_eq!(parse_pgm::<f64>(&binary).unwrap(), plain);
+        assert!(matches!(parse_pgm::<f64>(&binary[..binary.len() - 1]), Err(Error::Format(_))));
+        assert!(parse_pgm::<f64>(b"P6 1 1 255\n").is_err());
+        assert!(matches!(parse_pgm::<f64>(b"P5 18446744073709551615 2 255\n"), Err(Error::Format(_))));
+        assert!(matches!(parse_pgm::<f64>(b"P5 9223372036854775807 1 65535\n"), Err(Error::Format(_))));
+    }
+}
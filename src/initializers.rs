@@ -0,0 +1,67 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::float::Float;
+use crate::matrix::Matrix;
+
+/// Distribution of a layer's initial weights. `fan_in` and `fan_out` are the number
+/// of inputs and outputs of one unit; the Glorot and He variants scale with them.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Initializer {
+    Zeros,
+    Constant {value: f64},
+    /// Uniform in `[low, high)`. The default is `[-1, 1)`.
+    Uniform {low: f64, high: f64},
+    /// Uniform in `±sqrt(6 / (fan_in + fan_out))`.
+    GlorotUniform,
+    /// Uniform in `±sqrt(6 / fan_in)`, suited to ReLU activations.
+    HeUniform
+}
+
+impl Default for Initializer {
+    fn default() -> Initializer {
+        Initializer::Uniform {low: -1.0, high: 1.0}
+    }
+}
+
+impl Initializer {
+    pub fn initialize<T: Float>(&self, rows: usize, cols: usize, fan_in: usize, fan_out: usize) -> Matrix<T> {
+        let (low, high) = match *self {
+            Initializer::Zeros => return Matrix::new(rows, cols),
+            Initializer::Constant {value} => {
+                let mut matrix = Matrix::new(rows, cols);
+                matrix.add_scalar(T::from_f64(value));
+                return matrix;
+            }
+            Initializer::Uniform {low, high} => (low, high),
+            Initializer::GlorotUniform => {
+                let limit = (6.0 / (fan_in + fan_out).max(1) as f64).sqrt();
+                (-limit, limit)
+            }
+            Initializer::HeUniform => {
+                let limit = (6.0 / fan_in.max(1) as f64).sqrt();
+                (-limit, limit)
+            }
+        };
+        let mut rng = rand::thread_rng();
+        let data = (0..rows*cols).map(|_| T::from_f64(low + rng.gen::<f64>() * (high - low))).collect();
+        Matrix::from_vec(data, rows, cols).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test_initializers {
+    use super::*;
+
+    #[test]
+    fn test_initialize() {
+        let zeros: Matrix = Initializer::Zeros.initialize(2, 3, 2, 3);
+        assert_eq!(zeros, Matrix::new(2, 3));
+        let constant: Matrix = Initializer::Constant {value: 0.5}.initialize(1, 2, 1, 2);
+        assert_eq!(constant.get_data(), &vec![0.5, 0.5]);
+        let glorot: Matrix = Initializer::GlorotUniform.initialize(10, 14, 10, 14);
+        assert!(glorot.get_data().iter().all(|x| x.abs() <= 0.5));
+        let uniform: Matrix = Initializer::default().initialize(10, 10, 10, 10);
+        assert!(uniform.get_data().iter().all(|&x| (-1.0..1.0).contains(&x)));
+    }
+}
@@ -1,31 +1,61 @@
 use super::layer_interface::Layer;
+use crate::config::LayerConfig;
+use crate::error::Result;
+use crate::float::Float;
 use crate::matrix::Matrix;
 use crate::activation_function::ActivationFunction;
 
-pub struct ActivationLayer {
-    activation_function: Box<dyn ActivationFunction>,
-    last_input: Matrix,
+pub struct ActivationLayer<T: Float = f64> {
+    activation_function: Box<dyn ActivationFunction<T>>,
+    last_input: Matrix<T>,
     size: [usize; 2]
 }
 
-impl ActivationLayer {
-    pub fn new(activation_function: Box<dyn ActivationFunction>) -> ActivationLayer {
+impl<T: Float> ActivationLayer<T> {
+    pub fn new(activation_function: Box<dyn ActivationFunction<T>>) -> ActivationLayer<T> {
         ActivationLayer {activation_function, last_input: Matrix::new(0, 0), size: [0, 0]}
     }
 }
 
-impl Layer for ActivationLayer {
-    fn initialize(&mut self, input_size: [usize; 2]) {
+impl<T: Float> Layer<T> for ActivationLayer<T> {
+    fn initialize(&mut self, input_size: [usize; 2]) -> Result<()> {
         self.size = input_size;
+        Ok(())
     }
-    fn forward(&mut self, inputs: &Matrix) -> Matrix {
+    fn forward(&mut self, inputs: &Matrix<T>) -> Result<Matrix<T>> {
         self.last_input = inputs.clone();
-        self.activation_function.as_ref().forward(inputs)
+        self.infer(inputs)
     }
-    fn backwards(&mut self, output_error: &Matrix, _learning_rate: f64) -> Matrix {
-        self.activation_function.as_ref().backwards(&self.last_input).elementwise_mul(output_error).unwrap().clone()
+    fn infer(&self, inputs: &Matrix<T>) -> Result<Matrix<T>> {
+        Ok(self.activation_function.as_ref().forward(inputs))
+    }
+    fn backward(&mut self, output_error: &Matrix<T>) -> Result<Matrix<T>> {
+        let mut input_error = self.activation_function.as_ref().backwards(&self.last_input);
+        input_error.elementwise_mul(output_error)?;
+        Ok(input_error)
     }
     fn get_size(&self) -> [usize; 2] {
         self.size
     }
+    fn name(&self) -> String {
+        format!("activation({})", self.activation_function.name())
+    }
+    fn config(&self) -> Option<LayerConfig> {
+        Some(LayerConfig::Activation {activation: self.activation_function.config()?})
+    }
+}
+
+#[cfg(test)]
+mod test_activation_layer {
+    use super::*;
+    use crate::activation_function::Sigmoid;
+    use crate::gradcheck::check_layer;
+
+    #[test]
+    fn test_gradcheck_batch() {
+        let mut layer = ActivationLayer::new(Box::new(Sigmoid{}));
+        layer.initialize([1, 3]).unwrap();
+        let batch = Matrix::from_vec(vec![0.1, -2.0, 3.0, 0.5, -0.5, 1.5], 2, 3).unwrap();
+        assert!(check_layer(&mut layer, &batch, 1e-6).unwrap().max_error() < 1e-6);
+    }
 }
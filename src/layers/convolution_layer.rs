@@ -1,61 +1,102 @@
 use super::layer_interface::Layer;
+use crate::config::LayerConfig;
+use crate::error::{Error, Result};
+use crate::float::Float;
+use crate::initializers::Initializer;
 use crate::matrix::Matrix;
+use crate::regularization::{Constraint, Regularizer};
 
-pub struct ConvolutionalLayer {
-    kernel: Matrix,
+pub struct ConvolutionalLayer<T: Float = f64> {
+    kernel: Matrix<T>,
+    kernel_gradient: Matrix<T>,
     stride: usize,
     padding: usize,
     output_size: [usize; 2],
-    last_input: Matrix
+    last_input: Matrix<T>,
+    initializer: Initializer,
+    kernel_regularizer: Option<Regularizer>,
+    kernel_constraint: Option<Constraint>
 }
 
-impl ConvolutionalLayer {
-    pub fn new(kernel: Matrix, stride: usize, padding: usize) -> ConvolutionalLayer {
-        ConvolutionalLayer {kernel, stride, padding, output_size: [0, 0], last_input: Matrix::new(0, 0)}
+impl<T: Float> ConvolutionalLayer<T> {
+    pub fn new(kernel: Matrix<T>, stride: usize, padding: usize) -> ConvolutionalLayer<T> {
+        ConvolutionalLayer {
+            kernel_gradient: Matrix::new(kernel.get_num_rows(), kernel.get_num_cols()),
+            kernel,
+            stride,
+            padding,
+            output_size: [0, 0],
+            last_input: Matrix::new(0, 0),
+            initializer: Initializer::default(),
+            kernel_regularizer: None,
+            kernel_constraint: None
+        }
+    }
+    /// Layer with a `kernel_size` kernel drawn from `initializer`.
+    pub fn from_initializer(kernel_size: [usize; 2], stride: usize, padding: usize, initializer: Initializer) -> ConvolutionalLayer<T> {
+        let fan = kernel_size[0] * kernel_size[1];
+        let kernel = initializer.initialize(kernel_size[0], kernel_size[1], fan, fan);
+        ConvolutionalLayer {initializer, ..ConvolutionalLayer::new(kernel, stride, padding)}
+    }
+    pub fn kernel_regularizer(mut self, regularizer: Regularizer) -> ConvolutionalLayer<T> {
+        self.kernel_regularizer = Some(regularizer);
+        self
+    }
+    pub fn kernel_constraint(mut self, constraint: Constraint) -> ConvolutionalLayer<T> {
+        self.kernel_constraint = Some(constraint);
+        self
+    }
+    // position in the unpadded input, or None when it falls into the padding
+    fn input_position(&self, output_index: usize, kernel_index: usize, input_len: usize) -> Option<usize> {
+        let position = (output_index * self.stride + kernel_index).checked_sub(self.padding)?;
+        if position < input_len {
+            Some(position)
+        } else {
+            None
+        }
+    }
+    fn check_output_error(&self, output_error: &Matrix<T>) -> Result<()> {
+        let expected_size = Matrix::<T>::convolution_output_size(self.last_input.get_shape(), self.kernel.get_shape(), self.stride, self.padding)?;
+        if output_error.get_shape() != expected_size {
+            return Err(Error::shape_mismatch("ConvolutionalLayer::backward", expected_size, output_error.get_shape()));
+        }
+        Ok(())
     }
-    fn get_weight_error(&self, output_error: &Matrix, learning_rate: f64) -> Matrix {
-        let mut result: Vec<f64> = Vec::with_capacity(self.kernel.get_num_rows() * self.kernel.get_num_cols());
+    fn get_weight_error(&self, output_error: &Matrix<T>) -> Matrix<T> {
+        let mut result: Matrix<T> = Matrix::new(self.kernel.get_num_rows(), self.kernel.get_num_cols());
         for i in 0..output_error.get_num_rows() {
             for j in 0..output_error.get_num_cols() {
-                let mut counter = 0;
                 for k in 0..self.kernel.get_num_rows() {
+                    let Some(final_k) = self.input_position(i, k, self.last_input.get_num_rows()) else {
+                        continue;
+                    };
                     for l in 0..self.kernel.get_num_cols() {
-                        let mut final_k = i * self.stride + k;
-                        if final_k < self.padding || final_k >= self.last_input.get_num_rows() {
+                        let Some(final_l) = self.input_position(j, l, self.last_input.get_num_cols()) else {
                             continue;
-                        }
-                        final_k -= self.padding;
-                        let mut final_l = j * self.stride + l;
-                        if final_l < self.padding || final_l >= self.last_input.get_num_cols() {
-                            continue;
-                        }
-                        final_l -= self.padding;
-                        result[counter] += self.last_input.get(final_k, final_l) * output_error.get(i, j);
-                        counter += 1;
+                        };
+                        result[(k, l)] += self.last_input.get(final_k, final_l) * output_error.get(i, j);
                     }
                 }
             }
         }
-        result.iter_mut().for_each(|x| *x = (*x)*learning_rate);
-        Matrix::from_vec(result, self.kernel.get_num_rows(), self.kernel.get_num_cols())
+        if let Some(regularizer) = self.kernel_regularizer {
+            result += regularizer.gradient(&self.kernel);
+        }
+        result
     }
-    fn get_input_error(&self, output_error: &Matrix) -> Matrix {
-        let mut result: Matrix = Matrix::new(self.last_input.get_num_rows(), self.last_input.get_num_cols());
+    fn get_input_error(&self, output_error: &Matrix<T>) -> Matrix<T> {
+        let mut result: Matrix<T> = Matrix::new(self.last_input.get_num_rows(), self.last_input.get_num_cols());
         for i in 0..output_error.get_num_rows() {
             for j in 0..output_error.get_num_cols() {
                 for k in 0..self.kernel.get_num_rows() {
+                    let Some(final_k) = self.input_position(i, k, self.last_input.get_num_rows()) else {
+                        continue;
+                    };
                     for l in 0..self.kernel.get_num_cols() {
-                        let mut final_k = i * self.stride + k;
-                        if final_k < self.padding || final_k >= self.last_input.get_num_rows() {
-                            continue;
-                        }
-                        final_k -= self.padding;
-                        let mut final_l = j * self.stride + l;
-                        if final_l < self.padding || final_l >= self.last_input.get_num_cols() {
+                        let Some(final_l) = self.input_position(j, l, self.last_input.get_num_cols()) else {
                             continue;
-                        }
-                        final_l -= self.padding;
-                        result.set(final_k, final_l, result.get(final_k, final_l) + self.kernel.get(k, l) * output_error.get(i, j));
+                        };
+                        result[(final_k, final_l)] += self.kernel.get(k, l) * output_error.get(i, j);
                     }
                 }
             }
@@ -64,27 +105,99 @@ impl ConvolutionalLayer {
     }
 }
 
-impl Layer for ConvolutionalLayer {
-    fn initialize(&mut self, input_size: [usize; 2]) {
-        let output_num_rows = (input_size[0] - self.kernel.get_num_rows() + 2 * self.padding) / self.stride + 1;
-        let output_num_cols = (input_size[1] - self.kernel.get_num_cols() + 2 * self.padding) / self.stride + 1;
-        self.output_size = [output_num_rows, output_num_cols];
+impl<T: Float> Layer<T> for ConvolutionalLayer<T> {
+    fn initialize(&mut self, input_size: [usize; 2]) -> Result<()> {
+        self.output_size = Matrix::<T>::convolution_output_size(input_size, self.kernel.get_shape(), self.stride, self.padding)?;
+        Ok(())
     }
-    fn forward(&mut self, inputs: &Matrix) -> Matrix {
+    fn forward(&mut self, inputs: &Matrix<T>) -> Result<Matrix<T>> {
         self.last_input = inputs.clone();
+        self.infer(inputs)
+    }
+    fn infer(&self, inputs: &Matrix<T>) -> Result<Matrix<T>> {
         Matrix::convolve(inputs, &self.kernel, self.stride, self.padding)
     }
-    fn backwards(&mut self, output_error: &Matrix, learning_rate: f64) -> Matrix {
-        let weight_error = self.get_weight_error(output_error, learning_rate);
-        let input_error = self.get_input_error(output_error);
-        let result = self.kernel.sub_matrix(&weight_error);
-        match result {
-                Ok(_) => (),
-                Err(_) => panic!("Error in ConvolutionalLayer implementation of get_weight_error")
-            }
-        input_error
+    fn backward(&mut self, output_error: &Matrix<T>) -> Result<Matrix<T>> {
+        self.check_output_error(output_error)?;
+        let weight_error = self.get_weight_error(output_error);
+        self.kernel_gradient.add_matrix(&weight_error)?;
+        Ok(self.get_input_error(output_error))
     }
     fn get_size(&self) -> [usize; 2] {
         self.output_size
     }
+    fn name(&self) -> String {
+        "convolutional".to_string()
+    }
+    fn config(&self) -> Option<LayerConfig> {
+        Some(LayerConfig::Convolutional {
+            kernel_size: self.kernel.get_shape(),
+            stride: self.stride,
+            padding: self.padding,
+            initializer: self.initializer,
+            kernel_regularizer: self.kernel_regularizer,
+            kernel_constraint: self.kernel_constraint
+        })
+    }
+    fn regularization_loss(&self) -> T {
+        self.kernel_regularizer.map_or(T::zero(), |regularizer| regularizer.penalty(&self.kernel))
+    }
+    fn apply_constraints(&mut self) -> Result<()> {
+        match self.kernel_constraint {
+            Some(constraint) => constraint.apply(&mut self.kernel),
+            None => Ok(())
+        }
+    }
+    fn parameters(&self) -> Vec<&Matrix<T>> {
+        vec![&self.kernel]
+    }
+    fn parameters_mut(&mut self) -> Vec<&mut Matrix<T>> {
+        vec![&mut self.kernel]
+    }
+    fn gradients(&self) -> Vec<&Matrix<T>> {
+        vec![&self.kernel_gradient]
+    }
+    fn gradients_mut(&mut self) -> Vec<&mut Matrix<T>> {
+        vec![&mut self.kernel_gradient]
+    }
+}
+
+#[cfg(test)]
+mod test_convolutional_layer {
+    use super::*;
+    use crate::gradcheck::check_layer;
+
+    #[test]
+    fn test_backward() {
+        let kernel = Matrix::from_vec(vec![1.0, 1.0, 1.0, 1.0], 2, 2).unwrap();
+        let mut layer = ConvolutionalLayer::new(kernel, 1, 1);
+        layer.initialize([2, 2]).unwrap();
+        assert_eq!(layer.get_size(), [3, 3]);
+        let inputs = Matrix::from_vec(vec![1.0, 2.0, 3.0, 4.0], 2, 2).unwrap();
+        layer.forward(&inputs).unwrap();
+        let input_error = layer.backward(&Matrix::from_vec(vec![1.0; 9], 3, 3).unwrap()).unwrap();
+        // every input pixel is covered by all four kernel positions
+        assert_eq!(input_error, Matrix::from_vec(vec![4.0; 4], 2, 2).unwrap());
+        // and every kernel weight sees every input pixel exactly once
+        assert_eq!(layer.kernel_gradient, Matrix::from_vec(vec![10.0; 4], 2, 2).unwrap());
+    }
+
+    #[test]
+    fn test_invalid_kernel() {
+        let mut layer = ConvolutionalLayer::new(Matrix::<f64>::new(3, 3), 1, 0);
+        assert!(layer.initialize([2, 2]).is_err());
+    }
+
+    #[test]
+    fn test_gradcheck() {
+        let kernel = Matrix::from_vec(vec![0.5, -0.3, 0.8, 0.1, -0.6, 0.4], 2, 3).unwrap();
+        let input = Matrix::from_vec((0..20).map(|i| (i as f64 * 0.37).sin()).collect(), 4, 5).unwrap();
+        for (stride, padding) in [(1, 0), (2, 1), (1, 2)] {
+            let mut layer = ConvolutionalLayer::new(kernel.clone(), stride, padding).kernel_regularizer(Regularizer::L2(0.05));
+            layer.initialize([4, 5]).unwrap();
+            let report = check_layer(&mut layer, &input, 1e-6).unwrap();
+            assert_eq!(report.parameters.len(), 1);
+            assert!(report.max_error() < 1e-6, "stride {} padding {}: {:?}", stride, padding, report);
+        }
+    }
 }
@@ -1,43 +1,201 @@
 use super::layer_interface::Layer;
-use crate::matrix::Matrix;
+use crate::config::LayerConfig;
+use crate::error::{Error, Result};
+use crate::float::Float;
+use crate::initializers::Initializer;
+use crate::matrix::{Axis, Matrix};
+use crate::regularization::{Constraint, Regularizer};
 
-pub struct DenseLayer {
+pub struct DenseLayer<T: Float = f64> {
     size: [usize; 2],
     input_size: [usize; 2],
-    weights: Matrix,
-    biases: Matrix,
-    last_input: Matrix
+    weights: Matrix<T>,
+    biases: Matrix<T>,
+    weights_gradient: Matrix<T>,
+    biases_gradient: Matrix<T>,
+    last_input: Matrix<T>,
+    kernel_initializer: Initializer,
+    bias_initializer: Initializer,
+    kernel_regularizer: Option<Regularizer>,
+    bias_regularizer: Option<Regularizer>,
+    kernel_constraint: Option<Constraint>,
+    bias_constraint: Option<Constraint>
 }
 
-impl DenseLayer {
-    pub fn new(size: usize) -> DenseLayer {
+impl<T: Float> DenseLayer<T> {
+    pub fn new(size: usize) -> DenseLayer<T> {
         let matrix = Matrix::new(0, 0);
-        DenseLayer {size: [1, size], input_size: [0,0], weights: matrix.clone(), biases: matrix.clone(), last_input: matrix}
+        DenseLayer {
+            size: [1, size],
+            input_size: [0,0],
+            weights: matrix.clone(),
+            biases: matrix.clone(),
+            weights_gradient: matrix.clone(),
+            biases_gradient: matrix.clone(),
+            last_input: matrix,
+            kernel_initializer: Initializer::default(),
+            bias_initializer: Initializer::default(),
+            kernel_regularizer: None,
+            bias_regularizer: None,
+            kernel_constraint: None,
+            bias_constraint: None
+        }
+    }
+    pub fn kernel_initializer(mut self, initializer: Initializer) -> DenseLayer<T> {
+        self.kernel_initializer = initializer;
+        self
+    }
+    pub fn bias_initializer(mut self, initializer: Initializer) -> DenseLayer<T> {
+        self.bias_initializer = initializer;
+        self
+    }
+    pub fn kernel_regularizer(mut self, regularizer: Regularizer) -> DenseLayer<T> {
+        self.kernel_regularizer = Some(regularizer);
+        self
+    }
+    pub fn bias_regularizer(mut self, regularizer: Regularizer) -> DenseLayer<T> {
+        self.bias_regularizer = Some(regularizer);
+        self
+    }
+    pub fn kernel_constraint(mut self, constraint: Constraint) -> DenseLayer<T> {
+        self.kernel_constraint = Some(constraint);
+        self
+    }
+    pub fn bias_constraint(mut self, constraint: Constraint) -> DenseLayer<T> {
+        self.bias_constraint = Some(constraint);
+        self
     }
 }
 
-impl Layer for DenseLayer {
-    fn initialize(&mut self, input_size: [usize; 2]) {
+impl<T: Float> Layer<T> for DenseLayer<T> {
+    fn initialize(&mut self, input_size: [usize; 2]) -> Result<()> {
         if input_size[0] != 1 {
-            panic!("DenseLayer input size must be [1, n]");
+            return Err(Error::InvalidConfiguration(
+                format!("DenseLayer input size must be [1, n], got [{}, {}]", input_size[0], input_size[1])));
         }
         self.input_size = input_size;
-        self.weights = Matrix::new_random(self.input_size[1], self.size[1]);
-        self.biases = Matrix::new_random(1, self.size[1]);
+        self.weights = self.kernel_initializer.initialize(self.input_size[1], self.size[1], self.input_size[1], self.size[1]);
+        self.biases = self.bias_initializer.initialize(1, self.size[1], self.input_size[1], self.size[1]);
+        self.weights_gradient = Matrix::new(self.input_size[1], self.size[1]);
+        self.biases_gradient = Matrix::new(1, self.size[1]);
+        Ok(())
     }
-    fn forward(&mut self, inputs: &Matrix) -> Matrix {
+    fn forward(&mut self, inputs: &Matrix<T>) -> Result<Matrix<T>> {
         self.last_input = inputs.clone();
-        Matrix::mul(inputs, &self.weights).unwrap().add_matrix(&self.biases).unwrap().clone()
+        self.infer(inputs)
+    }
+    fn infer(&self, inputs: &Matrix<T>) -> Result<Matrix<T>> {
+        let mut outputs = inputs.matmul(&self.weights)?;
+        outputs.add_matrix(&self.biases)?;
+        Ok(outputs)
     }
 
-    fn backwards(&mut self, output_error: &Matrix, learning_rate: f64) -> Matrix {
-        let input_error = Matrix::mul(output_error, &Matrix::transpose(&self.weights));
-        let weights_error = Matrix::mul(&Matrix::transpose(&self.last_input), output_error);
-        let _ = self.weights.sub_matrix(weights_error.unwrap().mul_scalar(learning_rate));
-        let _ = self.biases.sub_matrix(output_error.clone().mul_scalar(learning_rate));
-        input_error.unwrap()
+    fn backward(&mut self, output_error: &Matrix<T>) -> Result<Matrix<T>> {
+        let input_error = output_error.matmul(self.weights.t())?;
+        self.weights_gradient.add_matrix(&self.last_input.t().matmul(output_error)?)?;
+        self.biases_gradient.add_matrix(&output_error.sum_axis(Axis::Cols))?;
+        if let Some(regularizer) = self.kernel_regularizer {
+            self.weights_gradient.add_matrix(&regularizer.gradient(&self.weights))?;
+        }
+        if let Some(regularizer) = self.bias_regularizer {
+            self.biases_gradient.add_matrix(&regularizer.gradient(&self.biases))?;
+        }
+        Ok(input_error)
     }
     fn get_size(&self) -> [usize; 2] {
         self.size
     }
+    fn name(&self) -> String {
+        "dense".to_string()
+    }
+    fn config(&self) -> Option<LayerConfig> {
+        Some(LayerConfig::Dense {
+            units: self.size[1],
+            kernel_initializer: self.kernel_initializer,
+            bias_initializer: self.bias_initializer,
+            kernel_regularizer: self.kernel_regularizer,
+            bias_regularizer: self.bias_regularizer,
+            kernel_constraint: self.kernel_constraint,
+            bias_constraint: self.bias_constraint
+        })
+    }
+    fn regularization_loss(&self) -> T {
+        let kernel_penalty = self.kernel_regularizer.map_or(T::zero(), |regularizer| regularizer.penalty(&self.weights));
+        let bias_penalty = self.bias_regularizer.map_or(T::zero(), |regularizer| regularizer.penalty(&self.biases));
+        kernel_penalty + bias_penalty
+    }
+    fn apply_constraints(&mut self) -> Result<()> {
+        if let Some(constraint) = self.kernel_constraint {
+            constraint.apply(&mut self.weights)?;
+        }
+        if let Some(constraint) = self.bias_constraint {
+            constraint.apply(&mut self.biases)?;
+        }
+        Ok(())
+    }
+    fn parameters(&self) -> Vec<&Matrix<T>> {
+        vec![&self.weights, &self.biases]
+    }
+    fn parameters_mut(&mut self) -> Vec<&mut Matrix<T>> {
+        vec![&mut self.weights, &mut self.biases]
+    }
+    fn gradients(&self) -> Vec<&Matrix<T>> {
+        vec![&self.weights_gradient, &self.biases_gradient]
+    }
+    fn gradients_mut(&mut self) -> Vec<&mut Matrix<T>> {
+        vec![&mut self.weights_gradient, &mut self.biases_gradient]
+    }
+}
+
+#[cfg(test)]
+mod test_dense_layer {
+    use super::*;
+    use crate::gradcheck::check_layer;
+
+    #[test]
+    fn test_batch() {
+        let mut layer: DenseLayer = DenseLayer::new(3);
+        layer.initialize([1, 2]).unwrap();
+        let single = Matrix::from_vec(vec![0.5, -1.0], 1, 2).unwrap();
+        let batch = Matrix::from_vec(vec![0.5, -1.0, 0.5, -1.0], 2, 2).unwrap();
+        let expected = layer.forward(&single).unwrap();
+        let outputs = layer.forward(&batch).unwrap();
+        assert_eq!(outputs, layer.infer(&batch).unwrap());
+        assert_eq!(outputs.get_shape(), [2, 3]);
+        for col in 0..3 {
+            assert_eq!(outputs.get(0, col), expected.get(0, col));
+            assert_eq!(outputs.get(1, col), expected.get(0, col));
+        }
+        let input_error = layer.backward(&Matrix::new(2, 3)).unwrap();
+        assert_eq!(input_error.get_shape(), [2, 2]);
+    }
+
+    #[test]
+    fn test_regularization_and_constraints() {
+        let mut layer: DenseLayer = DenseLayer::new(2)
+            .kernel_regularizer(Regularizer::L2(0.25))
+            .kernel_constraint(Constraint::NonNegative);
+        layer.initialize([1, 2]).unwrap();
+        *layer.parameters_mut()[0] = Matrix::from_vec(vec![2.0, -2.0, 4.0, 0.0], 2, 2).unwrap();
+        assert_eq!(layer.regularization_loss(), 6.0);
+        layer.forward(&Matrix::new(1, 2)).unwrap();
+        layer.backward(&Matrix::new(1, 2)).unwrap();
+        assert_eq!(layer.weights_gradient.get_data(), &vec![1.0, -1.0, 2.0, 0.0]);
+        layer.weights.sub_matrix(&layer.weights_gradient).unwrap();
+        layer.apply_constraints().unwrap();
+        assert_eq!(layer.weights.get_data(), &vec![1.0, 0.0, 2.0, 0.0]);
+        layer.zero_grad();
+        assert_eq!(layer.weights_gradient, Matrix::new(2, 2));
+    }
+
+    #[test]
+    fn test_gradcheck() {
+        let mut layer: DenseLayer = DenseLayer::new(3).kernel_regularizer(Regularizer::ElasticNet {l1: 0.01, l2: 0.1})
+            .bias_regularizer(Regularizer::L2(0.1));
+        layer.initialize([1, 4]).unwrap();
+        let input = Matrix::from_vec(vec![0.5, -1.0, 0.25, 2.0], 1, 4).unwrap();
+        assert!(check_layer(&mut layer, &input, 1e-6).unwrap().max_error() < 1e-6);
+        let batch = Matrix::from_vec(vec![0.5, -1.0, 0.25, 2.0, 1.5, 0.0, -0.5, 1.0], 2, 4).unwrap();
+        assert!(check_layer(&mut layer, &batch, 1e-6).unwrap().max_error() < 1e-6);
+    }
 }
@@ -1,6 +1,10 @@
 use super::layer_interface::Layer;
+use crate::config::LayerConfig;
+use crate::error::Result;
+use crate::float::Float;
 use crate::matrix::Matrix;
 
+#[derive(Default)]
 pub struct FlattenLayer {
     input_size: [usize; 2],
     output_size: [usize; 2]
@@ -12,18 +16,44 @@ impl FlattenLayer {
     }
 }
 
-impl Layer for FlattenLayer {
-    fn initialize(&mut self, input_size: [usize; 2]) {
+impl<T: Float> Layer<T> for FlattenLayer {
+    fn initialize(&mut self, input_size: [usize; 2]) -> Result<()> {
         self.input_size = input_size;
         self.output_size = [1, input_size[0] * input_size[1]];
+        Ok(())
     }
-    fn forward(&mut self, inputs: &Matrix) -> Matrix {
-        Matrix::from_vec(inputs.get_data().clone(), self.output_size[0], self.output_size[1])
+    fn forward(&mut self, inputs: &Matrix<T>) -> Result<Matrix<T>> {
+        self.infer(inputs)
     }
-    fn backwards(&mut self, output_error: &Matrix, _learning_rate: f64) -> Matrix {
-        Matrix::from_vec(output_error.get_data().clone(), self.input_size[0], self.input_size[1])
+    fn infer(&self, inputs: &Matrix<T>) -> Result<Matrix<T>> {
+        inputs.clone().reshape(self.output_size[0], self.output_size[1])
+    }
+    fn backward(&mut self, output_error: &Matrix<T>) -> Result<Matrix<T>> {
+        output_error.clone().reshape(self.input_size[0], self.input_size[1])
     }
     fn get_size(&self) -> [usize; 2] {
         self.output_size
     }
+    fn name(&self) -> String {
+        "flatten".to_string()
+    }
+    fn config(&self) -> Option<LayerConfig> {
+        Some(LayerConfig::Flatten)
+    }
+}
+
+#[cfg(test)]
+mod test_flatten_layer {
+    use super::*;
+    use crate::gradcheck::check_layer;
+
+    #[test]
+    fn test_gradcheck() {
+        let mut layer = FlattenLayer::new();
+        Layer::<f64>::initialize(&mut layer, [2, 3]).unwrap();
+        let input = Matrix::from_vec(vec![0.1, -0.2, 0.3, -0.4, 0.5, -0.6], 2, 3).unwrap();
+        let report = check_layer(&mut layer, &input, 1e-6).unwrap();
+        assert!(report.parameters.is_empty());
+        assert!(report.input < 1e-6);
+    }
 }
@@ -1,23 +1,180 @@
+use crate::config::LayerConfig;
+use crate::error::{Error, Result};
+use crate::float::Float;
 use crate::matrix::Matrix;
 
-pub trait Layer {
-    fn forward(&mut self, inputs: &Matrix) -> Matrix;
-    fn backwards(&mut self, output_error: &Matrix, learning_rate: f64) -> Matrix;
-    fn initialize(&mut self, _input_size: [usize; 2]) {}
+/// A differentiable layer. `backward` returns the input error and adds the
+/// parameter gradients to `gradients()`, leaving the parameters untouched; the
+/// caller applies the update and then clears the gradients with `zero_grad`.
+///
+/// Layers written against the previous interface, which implement `backwards` and
+/// update their own weights, still compile and train; see `backwards`.
+pub trait Layer<T: Float = f64>: Send + Sync {
+    fn forward(&mut self, inputs: &Matrix<T>) -> Result<Matrix<T>>;
+    /// Same output as `forward` without caching anything for `backward`, so a trained
+    /// layer can be shared between threads for prediction.
+    fn infer(&self, _inputs: &Matrix<T>) -> Result<Matrix<T>> {
+        Err(Error::InvalidConfiguration("Layer does not support stateless inference".to_owned()))
+    }
+    fn backward(&mut self, _output_error: &Matrix<T>) -> Result<Matrix<T>> {
+        Err(Error::InvalidConfiguration("Layer updates its weights in `backwards` and does not compute gradients".to_owned()))
+    }
+    /// Backward pass used by `NN` training. The default computes the gradients with
+    /// `backward` and leaves the update to the network. Layers of the previous
+    /// interface override it to update their own weights with the network's learning
+    /// rate; they take no part in gradient clipping, accumulation or averaging over a
+    /// batch, are rejected by gradient checking and data-parallel training, and need
+    /// `infer` for `NN::predict`.
+    fn backwards(&mut self, output_error: &Matrix<T>, _learning_rate: T) -> Result<Matrix<T>> {
+        self.backward(output_error)
+    }
+    fn initialize(&mut self, _input_size: [usize; 2]) -> Result<()> {
+        Ok(())
+    }
     fn get_size(&self) -> [usize; 2];
+    /// Short name of the layer type shown in `NN::summary`.
+    fn name(&self) -> String {
+        "layer".to_string()
+    }
+    /// Description used by `NN::get_config`, `None` for layers it cannot express.
+    fn config(&self) -> Option<LayerConfig> {
+        None
+    }
+    /// Trainable matrices in a fixed order, empty for layers without weights.
+    fn parameters(&self) -> Vec<&Matrix<T>> {
+        Vec::new()
+    }
+    fn parameters_mut(&mut self) -> Vec<&mut Matrix<T>> {
+        Vec::new()
+    }
+    /// Accumulated gradients, matching `parameters()` in order and shape.
+    fn gradients(&self) -> Vec<&Matrix<T>> {
+        Vec::new()
+    }
+    fn gradients_mut(&mut self) -> Vec<&mut Matrix<T>> {
+        Vec::new()
+    }
+    /// State that belongs to the model but is not updated by gradient descent.
+    fn non_trainable_parameters(&self) -> Vec<&Matrix<T>> {
+        Vec::new()
+    }
+    fn zero_grad(&mut self) {
+        for gradient in self.gradients_mut() {
+            gradient.get_data_mut().iter_mut().for_each(|x| *x = T::zero());
+        }
+    }
+    /// Penalty of the layer's weight regularizers, added to the training loss.
+    fn regularization_loss(&self) -> T {
+        T::zero()
+    }
+    /// Projects the parameters back onto their constraints after an update.
+    fn apply_constraints(&mut self) -> Result<()> {
+        Ok(())
+    }
 }
 
-impl Layer for Box<dyn Layer> {
-    fn forward(&mut self, inputs: &Matrix) -> Matrix {
+impl<T: Float> Layer<T> for Box<dyn Layer<T>> {
+    fn forward(&mut self, inputs: &Matrix<T>) -> Result<Matrix<T>> {
         self.as_mut().forward(inputs)
     }
-    fn initialize(&mut self, input_size: [usize; 2]) {
-        self.as_mut().initialize(input_size)
+    fn infer(&self, inputs: &Matrix<T>) -> Result<Matrix<T>> {
+        self.as_ref().infer(inputs)
     }
-    fn backwards(&mut self, output_error: &Matrix, learning_rate: f64) -> Matrix {
+    fn backward(&mut self, output_error: &Matrix<T>) -> Result<Matrix<T>> {
+        self.as_mut().backward(output_error)
+    }
+    fn backwards(&mut self, output_error: &Matrix<T>, learning_rate: T) -> Result<Matrix<T>> {
         self.as_mut().backwards(output_error, learning_rate)
     }
+    fn initialize(&mut self, input_size: [usize; 2]) -> Result<()> {
+        self.as_mut().initialize(input_size)
+    }
     fn get_size(&self) -> [usize; 2] {
         self.as_ref().get_size()
     }
+    fn name(&self) -> String {
+        self.as_ref().name()
+    }
+    fn config(&self) -> Option<LayerConfig> {
+        self.as_ref().config()
+    }
+    fn parameters(&self) -> Vec<&Matrix<T>> {
+        self.as_ref().parameters()
+    }
+    fn parameters_mut(&mut self) -> Vec<&mut Matrix<T>> {
+        self.as_mut().parameters_mut()
+    }
+    fn gradients(&self) -> Vec<&Matrix<T>> {
+        self.as_ref().gradients()
+    }
+    fn gradients_mut(&mut self) -> Vec<&mut Matrix<T>> {
+        self.as_mut().gradients_mut()
+    }
+    fn non_trainable_parameters(&self) -> Vec<&Matrix<T>> {
+        self.as_ref().non_trainable_parameters()
+    }
+    fn zero_grad(&mut self) {
+        self.as_mut().zero_grad()
+    }
+    fn regularization_loss(&self) -> T {
+        self.as_ref().regularization_loss()
+    }
+    fn apply_constraints(&mut self) -> Result<()> {
+        self.as_mut().apply_constraints()
+    }
+}
+
+/// Fails for layers that expose parameters without matching gradients.
+pub(crate) fn check_gradients<T: Float>(layer: &dyn Layer<T>, index: usize, operation: &str) -> Result<()> {
+    if layer.gradients().len() != layer.parameters().len() {
+        return Err(Error::InvalidConfiguration(
+            format!("{} needs gradients from every layer, but layer {} ({}) does not provide them", operation, index, layer.name())));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_layer_interface {
+    use super::*;
+    use crate::dataset::VecDataset;
+    use crate::gradcheck::check_layer;
+    use crate::neural_network::NN;
+
+    // written against the previous interface and left as it was
+    struct Scale(f64);
+
+    impl Layer for Scale {
+        fn forward(&mut self, inputs: &Matrix) -> Result<Matrix> {
+            Ok(inputs * self.0)
+        }
+        fn backwards(&mut self, output_error: &Matrix, learning_rate: f64) -> Result<Matrix> {
+            self.0 -= learning_rate;
+            Ok(output_error * (self.0 + learning_rate))
+        }
+        fn get_size(&self) -> [usize; 2] {
+            [1, 1]
+        }
+    }
+
+    #[test]
+    fn test_previous_interface() {
+        let mut layer: Box<dyn Layer> = Box::new(Scale(2.0));
+        assert_eq!(layer.forward(&Matrix::from_vec(vec![3.0], 1, 1).unwrap()).unwrap().get(0, 0), 6.0);
+        assert!(layer.backward(&Matrix::from_vec(vec![1.0], 1, 1).unwrap()).is_err());
+        assert_eq!(layer.backwards(&Matrix::from_vec(vec![1.0], 1, 1).unwrap(), 0.5).unwrap().get(0, 0), 2.0);
+        assert_eq!(layer.forward(&Matrix::from_vec(vec![3.0], 1, 1).unwrap()).unwrap().get(0, 0), 4.5);
+        assert!(layer.infer(&Matrix::from_vec(vec![3.0], 1, 1).unwrap()).is_err());
+        assert!(check_layer(&mut layer, &Matrix::from_vec(vec![3.0], 1, 1).unwrap(), 1e-6).is_err());
+        assert_eq!(layer.forward(&Matrix::from_vec(vec![1.0], 1, 1).unwrap()).unwrap().get(0, 0), 1.5);
+    }
+
+    #[test]
+    fn test_previous_interface_uses_network_learning_rate() {
+        let mut nn = NN::new([1, 1], 0.25);
+        nn.add(Box::new(Scale(2.0))).unwrap();
+        let input = Matrix::from_vec(vec![1.0], 1, 1).unwrap();
+        let dataset = VecDataset::new(vec![input.clone()], vec![Matrix::from_vec(vec![0.0], 1, 1).unwrap()]).unwrap();
+        nn.train(&dataset, 1).unwrap();
+        assert_eq!(nn.forward(&input).unwrap().get(0, 0), 1.75);
+    }
 }
@@ -1,5 +1,21 @@
 pub mod activation_function;
+pub mod builder;
+pub mod callbacks;
+pub mod clipping;
+pub mod config;
+pub mod data_loader;
+pub mod dataset;
+pub mod error;
+pub mod float;
+pub mod gradcheck;
+pub mod image;
+pub mod initializers;
 pub mod matrix;
+pub mod metrics;
 pub mod neural_network;
 pub mod mnist;
 pub mod layers;
+pub mod preprocessing;
+pub mod regularization;
+pub mod summary;
+pub mod training;
@@ -0,0 +1,88 @@
+use super::Matrix;
+use crate::error::{Error, Result};
+use crate::float::Float;
+
+impl<T: Float> Matrix<T> {
+    /// Shape of the result of a broadcasting elementwise operation. Each dimension
+    /// must either match or be 1, so row vectors, column vectors and `[1, 1]`
+    /// scalars stretch over the other operand.
+    pub fn broadcast_shape(left: [usize; 2], right: [usize; 2]) -> Result<[usize; 2]> {
+        let mut shape = [0; 2];
+        for axis in 0..2 {
+            shape[axis] = match (left[axis], right[axis]) {
+                (a, b) if a == b => a,
+                (1, b) => b,
+                (a, 1) => a,
+                _ => return Err(Error::shape_mismatch("broadcast", left, right))
+            };
+        }
+        Ok(shape)
+    }
+
+    fn broadcast_get(&self, row: usize, col: usize) -> T {
+        let row = if self.rows == 1 { 0 } else { row };
+        let col = if self.cols == 1 { 0 } else { col };
+        self.data[row*self.cols + col]
+    }
+
+    pub(super) fn zip_broadcast(&self, other: &Matrix<T>, operation: &'static str, f: impl Fn(T, T) -> T) -> Result<Matrix<T>> {
+        let [rows, cols] = Matrix::<T>::broadcast_shape(self.get_shape(), other.get_shape())
+            .map_err(|_| Error::shape_mismatch(operation, self.get_shape(), other.get_shape()))?;
+        let mut data = Vec::with_capacity(rows*cols);
+        for i in 0..rows {
+            for j in 0..cols {
+                data.push(f(self.broadcast_get(i, j), other.broadcast_get(i, j)));
+            }
+        }
+        Ok(Matrix {rows, cols, data})
+    }
+
+    // in-place variant: `other` is stretched to the shape of `self`, never the other way round
+    pub(super) fn zip_assign_broadcast(&mut self, other: &Matrix<T>, operation: &'static str, f: impl Fn(&mut T, T)) -> Result<()> {
+        let shape = Matrix::<T>::broadcast_shape(self.get_shape(), other.get_shape());
+        if shape.ok() != Some(self.get_shape()) {
+            return Err(Error::shape_mismatch(operation, self.get_shape(), other.get_shape()));
+        }
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                f(&mut self.data[i*self.cols + j], other.broadcast_get(i, j));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_broadcast {
+    use super::*;
+
+    #[test]
+    fn test_row_and_column_vectors() {
+        let mut m = Matrix::from_vec(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 2, 3).unwrap();
+        m.add_matrix(&Matrix::from_vec(vec![10.0, 20.0, 30.0], 1, 3).unwrap()).unwrap();
+        assert_eq!(m, Matrix::from_vec(vec![11.0, 22.0, 33.0, 14.0, 25.0, 36.0], 2, 3).unwrap());
+        m.elementwise_mul(&Matrix::from_vec(vec![1.0, -1.0], 2, 1).unwrap()).unwrap();
+        assert_eq!(m, Matrix::from_vec(vec![11.0, 22.0, 33.0, -14.0, -25.0, -36.0], 2, 3).unwrap());
+        m.sub_matrix(&Matrix::from_vec(vec![1.0], 1, 1).unwrap()).unwrap();
+        assert_eq!(m.get(0, 0), 10.0);
+    }
+
+    #[test]
+    fn test_outer() {
+        let column = Matrix::from_vec(vec![1.0, 2.0], 2, 1).unwrap();
+        let row = Matrix::from_vec(vec![10.0, 20.0, 30.0], 1, 3).unwrap();
+        assert_eq!(&column + &row, Matrix::from_vec(vec![11.0, 21.0, 31.0, 12.0, 22.0, 32.0], 2, 3).unwrap());
+        // the in-place form cannot grow `self`
+        assert!(column.clone().add_matrix(&row).is_err());
+    }
+
+    #[test]
+    fn test_incompatible() {
+        let a = Matrix::<f64>::new(2, 3);
+        let b = Matrix::<f64>::new(3, 2);
+        match Matrix::<f64>::broadcast_shape(a.get_shape(), b.get_shape()) {
+            Err(Error::ShapeMismatch {left, right, ..}) => assert_eq!((left, right), ([2, 3], [3, 2])),
+            _ => panic!("expected a shape mismatch")
+        }
+    }
+}
@@ -0,0 +1,558 @@
+use super::Matrix;
+use crate::error::{Error, Result};
+use crate::float::Float;
+
+const MAX_SWEEPS: usize = 100;
+
+/// LU decomposition with partial pivoting, `P A = L U`.
+#[derive(Clone, Debug)]
+pub struct Lu<T: Float = f64> {
+    // L (unit diagonal, not stored) and U packed into one matrix
+    lu: Matrix<T>,
+    permutation: Vec<usize>,
+    swaps: usize
+}
+
+/// Thin QR decomposition `A = Q R`, with `Q` of shape `[rows, k]` and `R` of shape `[k, cols]`, `k = min(rows, cols)`.
+#[derive(Clone, Debug)]
+pub struct Qr<T: Float = f64> {
+    pub q: Matrix<T>,
+    pub r: Matrix<T>
+}
+
+/// Eigen-decomposition of a symmetric matrix, eigenvalues in descending order and
+/// the matching eigenvectors stored as columns.
+#[derive(Clone, Debug)]
+pub struct SymmetricEigen<T: Float = f64> {
+    pub eigenvalues: Vec<T>,
+    pub eigenvectors: Matrix<T>
+}
+
+/// Thin singular value decomposition `A = U diag(S) Vt`, singular values in descending order.
+/// Columns of `u` belonging to zero singular values are left as zeros.
+#[derive(Clone, Debug)]
+pub struct Svd<T: Float = f64> {
+    pub u: Matrix<T>,
+    pub singular_values: Vec<T>,
+    pub vt: Matrix<T>
+}
+
+impl<T: Float> Lu<T> {
+    pub fn get_l(&self) -> Matrix<T> {
+        let n = self.lu.rows;
+        let mut l = Matrix::identity(n);
+        for i in 0..n {
+            for j in 0..i {
+                l[(i, j)] = self.lu[(i, j)];
+            }
+        }
+        l
+    }
+
+    pub fn get_u(&self) -> Matrix<T> {
+        let n = self.lu.rows;
+        let mut u = Matrix::new(n, n);
+        for i in 0..n {
+            for j in i..n {
+                u[(i, j)] = self.lu[(i, j)];
+            }
+        }
+        u
+    }
+
+    /// Row `i` of `P A` is row `get_permutation()[i]` of `A`.
+    pub fn get_permutation(&self) -> &Vec<usize> {
+        &self.permutation
+    }
+
+    pub fn determinant(&self) -> T {
+        let product = (0..self.lu.rows).map(|i| self.lu[(i, i)]).fold(T::one(), |a, b| a * b);
+        if self.swaps.is_multiple_of(2) { product } else { -product }
+    }
+
+    pub fn is_singular(&self) -> bool {
+        let n = self.lu.rows;
+        let scale = self.lu.data.iter().fold(T::zero(), |a, &b| a.max(b.abs()));
+        let tolerance = T::epsilon() * T::from_usize(n.max(1)) * scale;
+        (0..n).any(|i| self.lu[(i, i)].abs() <= tolerance)
+    }
+
+    pub fn solve(&self, b: &Matrix<T>) -> Result<Matrix<T>> {
+        let n = self.lu.rows;
+        if b.rows != n {
+            return Err(Error::shape_mismatch("solve", self.lu.get_shape(), b.get_shape()));
+        }
+        if self.is_singular() {
+            return Err(Error::Numerical("Matrix is singular".to_string()));
+        }
+        let mut x = Matrix::new(n, b.cols);
+        for col in 0..b.cols {
+            // forward substitution with the unit lower triangle
+            for i in 0..n {
+                let mut sum = b[(self.permutation[i], col)];
+                for k in 0..i {
+                    sum -= self.lu[(i, k)] * x[(k, col)];
+                }
+                x[(i, col)] = sum;
+            }
+            // back substitution with the upper triangle
+            for i in (0..n).rev() {
+                let mut sum = x[(i, col)];
+                for k in i + 1..n {
+                    sum -= self.lu[(i, k)] * x[(k, col)];
+                }
+                x[(i, col)] = sum / self.lu[(i, i)];
+            }
+        }
+        Ok(x)
+    }
+
+    pub fn inverse(&self) -> Result<Matrix<T>> {
+        self.solve(&Matrix::identity(self.lu.rows))
+    }
+}
+
+fn check_square<T: Float>(matrix: &Matrix<T>, operation: &'static str) -> Result<()> {
+    if matrix.rows != matrix.cols {
+        return Err(Error::shape_mismatch(operation, matrix.get_shape(), [matrix.cols, matrix.rows]));
+    }
+    Ok(())
+}
+
+// iterative decompositions would otherwise spin on NaN or infinite entries
+fn check_finite<T: Float>(matrix: &Matrix<T>, operation: &str) -> Result<()> {
+    if !matrix.data.iter().all(|x| x.is_finite()) {
+        return Err(Error::Numerical(format!("Matrix passed to {} contains non-finite values", operation)));
+    }
+    Ok(())
+}
+
+fn sign<T: Float>(x: T) -> T {
+    if x < T::zero() { -T::one() } else { T::one() }
+}
+
+fn sort_descending<T: Float>(values: &[T]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[b].partial_cmp(&values[a]).unwrap_or(std::cmp::Ordering::Equal));
+    order
+}
+
+// applies the rotation [[c, s], [-s, c]] to columns p and q
+fn rotate_columns<T: Float>(matrix: &mut Matrix<T>, p: usize, q: usize, c: T, s: T) {
+    for k in 0..matrix.rows {
+        let a = matrix[(k, p)];
+        let b = matrix[(k, q)];
+        matrix[(k, p)] = c * a - s * b;
+        matrix[(k, q)] = s * a + c * b;
+    }
+}
+
+fn rotate_rows<T: Float>(matrix: &mut Matrix<T>, p: usize, q: usize, c: T, s: T) {
+    for k in 0..matrix.cols {
+        let a = matrix[(p, k)];
+        let b = matrix[(q, k)];
+        matrix[(p, k)] = c * a - s * b;
+        matrix[(q, k)] = s * a + c * b;
+    }
+}
+
+impl<T: Float> Matrix<T> {
+    pub fn identity(size: usize) -> Matrix<T> {
+        let mut result = Matrix::new(size, size);
+        for i in 0..size {
+            result[(i, i)] = T::one();
+        }
+        result
+    }
+
+    /// Random matrix with orthonormal columns (or rows, when `rows < cols`).
+    pub fn new_orthogonal(rows: usize, cols: usize) -> Result<Matrix<T>> {
+        let random: Matrix<T> = Matrix::new_random(rows.max(cols), rows.min(cols));
+        let Qr {mut q, r} = random.qr()?;
+        // fix the signs so the result does not depend on the Householder convention
+        for j in 0..q.cols {
+            if r[(j, j)] < T::zero() {
+                q.col_mut(j)?.map_inplace(|x| -x);
+            }
+        }
+        if rows < cols {
+            Ok(Matrix::transpose(&q))
+        } else {
+            Ok(q)
+        }
+    }
+
+    pub fn lu(&self) -> Result<Lu<T>> {
+        check_square(self, "lu")?;
+        let n = self.rows;
+        let mut lu = self.clone();
+        let mut permutation: Vec<usize> = (0..n).collect();
+        let mut swaps = 0;
+        for k in 0..n {
+            let pivot = (k..n).max_by(|&a, &b| lu[(a, k)].abs().partial_cmp(&lu[(b, k)].abs()).unwrap_or(std::cmp::Ordering::Equal)).unwrap();
+            if pivot != k {
+                for j in 0..n {
+                    lu.data.swap(k*n + j, pivot*n + j);
+                }
+                permutation.swap(k, pivot);
+                swaps += 1;
+            }
+            let diagonal = lu[(k, k)];
+            if diagonal == T::zero() {
+                continue;
+            }
+            for i in k + 1..n {
+                let factor = lu[(i, k)] / diagonal;
+                lu[(i, k)] = factor;
+                for j in k + 1..n {
+                    let value = lu[(k, j)];
+                    lu[(i, j)] -= factor * value;
+                }
+            }
+        }
+        Ok(Lu {lu, permutation, swaps})
+    }
+
+    pub fn solve(&self, b: &Matrix<T>) -> Result<Matrix<T>> {
+        self.lu()?.solve(b)
+    }
+
+    pub fn inverse(&self) -> Result<Matrix<T>> {
+        self.lu()?.inverse()
+    }
+
+    pub fn determinant(&self) -> Result<T> {
+        Ok(self.lu()?.determinant())
+    }
+
+    /// Lower triangular `L` with `A = L Lᵀ`, for symmetric positive definite `A`.
+    pub fn cholesky(&self) -> Result<Matrix<T>> {
+        check_square(self, "cholesky")?;
+        let n = self.rows;
+        let mut l = Matrix::new(n, n);
+        for j in 0..n {
+            let mut diagonal = self[(j, j)];
+            for k in 0..j {
+                diagonal -= l[(j, k)] * l[(j, k)];
+            }
+            if diagonal <= T::zero() || diagonal.is_nan() {
+                return Err(Error::Numerical("Matrix is not positive definite".to_string()));
+            }
+            let diagonal = diagonal.sqrt();
+            l[(j, j)] = diagonal;
+            for i in j + 1..n {
+                let mut sum = self[(i, j)];
+                for k in 0..j {
+                    sum -= l[(i, k)] * l[(j, k)];
+                }
+                l[(i, j)] = sum / diagonal;
+            }
+        }
+        Ok(l)
+    }
+
+    /// Householder QR decomposition.
+    pub fn qr(&self) -> Result<Qr<T>> {
+        let (m, n) = (self.rows, self.cols);
+        let k = m.min(n);
+        let mut r = self.clone();
+        let mut q: Matrix<T> = Matrix::identity(m);
+        for step in 0..k {
+            let norm = (step..m).map(|i| r[(i, step)] * r[(i, step)]).sum::<T>().sqrt();
+            if norm == T::zero() {
+                continue;
+            }
+            let alpha = -sign(r[(step, step)]) * norm;
+            let mut v: Vec<T> = (step..m).map(|i| r[(i, step)]).collect();
+            v[0] -= alpha;
+            let v_norm = v.iter().map(|&x| x * x).sum::<T>().sqrt();
+            if v_norm == T::zero() {
+                continue;
+            }
+            v.iter_mut().for_each(|x| *x /= v_norm);
+            let two = T::from_f64(2.0);
+            // R <- (I - 2 v vᵀ) R
+            for j in 0..n {
+                let dot: T = v.iter().enumerate().map(|(i, &vi)| vi * r[(step + i, j)]).sum();
+                for (i, &vi) in v.iter().enumerate() {
+                    r[(step + i, j)] -= two * vi * dot;
+                }
+            }
+            // Q <- Q (I - 2 v vᵀ)
+            for row in 0..m {
+                let dot: T = v.iter().enumerate().map(|(i, &vi)| q[(row, step + i)] * vi).sum();
+                for (i, &vi) in v.iter().enumerate() {
+                    q[(row, step + i)] -= two * dot * vi;
+                }
+            }
+        }
+        let q = q.slice(0..m, 0..k)?.to_matrix();
+        let mut r = r.slice(0..k, 0..n)?.to_matrix();
+        for i in 0..k {
+            for j in 0..i.min(n) {
+                r[(i, j)] = T::zero();
+            }
+        }
+        Ok(Qr {q, r})
+    }
+
+    /// Minimises `|A x - b|` for `A` with at least as many rows as columns and full column rank.
+    pub fn least_squares(&self, b: &Matrix<T>) -> Result<Matrix<T>> {
+        if self.rows < self.cols || b.rows != self.rows {
+            return Err(Error::shape_mismatch("least_squares", self.get_shape(), b.get_shape()));
+        }
+        let Qr {q, r} = self.qr()?;
+        let qtb = q.t().matmul(b)?;
+        let n = self.cols;
+        let scale = r.data.iter().fold(T::zero(), |a, &x| a.max(x.abs()));
+        let mut x = Matrix::new(n, b.cols);
+        for col in 0..b.cols {
+            for i in (0..n).rev() {
+                if r[(i, i)].abs() <= T::epsilon() * T::from_usize(self.rows) * scale {
+                    return Err(Error::Numerical("Matrix does not have full column rank".to_string()));
+                }
+                let mut sum = qtb[(i, col)];
+                for k in i + 1..n {
+                    sum -= r[(i, k)] * x[(k, col)];
+                }
+                x[(i, col)] = sum / r[(i, i)];
+            }
+        }
+        Ok(x)
+    }
+
+    /// Cyclic Jacobi eigenvalue algorithm for symmetric matrices.
+    pub fn symmetric_eigen(&self) -> Result<SymmetricEigen<T>> {
+        self.jacobi_eigen().map(|(eigen, _)| eigen)
+    }
+
+    // Returns the decomposition together with the number of sweeps that rotated anything.
+    fn jacobi_eigen(&self) -> Result<(SymmetricEigen<T>, usize)> {
+        check_square(self, "symmetric_eigen")?;
+        check_finite(self, "symmetric_eigen")?;
+        let n = self.rows;
+        let scale = self.data.iter().fold(T::one(), |a, &x| a.max(x.abs()));
+        let tolerance = T::epsilon().sqrt() * scale;
+        for i in 0..n {
+            for j in 0..i {
+                if (self[(i, j)] - self[(j, i)]).abs() > tolerance {
+                    return Err(Error::Numerical("Matrix is not symmetric".to_string()));
+                }
+            }
+        }
+        let mut a = self.clone();
+        // eigenvectors are accumulated as rows so every update walks memory contiguously
+        let mut vt: Matrix<T> = Matrix::identity(n);
+        let two = T::from_f64(2.0);
+        // rotations preserve the Frobenius norm, so the stopping threshold is fixed up front
+        let frobenius: T = self.data.iter().map(|&x| x * x).sum();
+        let threshold = T::epsilon() * T::epsilon() * frobenius;
+        let mut sweeps = 0;
+        let mut converged = false;
+        while sweeps < MAX_SWEEPS {
+            let off_diagonal: T = (0..n).flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+                .map(|(i, j)| a[(i, j)] * a[(i, j)]).sum();
+            if off_diagonal <= threshold {
+                converged = true;
+                break;
+            }
+            let mut rotated = false;
+            for p in 0..n {
+                for q in p + 1..n {
+                    let apq = a[(p, q)];
+                    if apq == T::zero() || apq.abs() <= T::epsilon() * (a[(p, p)] * a[(q, q)]).abs().sqrt() {
+                        continue;
+                    }
+                    rotated = true;
+                    let theta = (a[(q, q)] - a[(p, p)]) / (two * apq);
+                    let t = sign(theta) / (theta.abs() + (theta * theta + T::one()).sqrt());
+                    let c = T::one() / (t * t + T::one()).sqrt();
+                    let s = t * c;
+                    // rows p and q are contiguous; symmetry gives the matching columns for free
+                    for k in (0..n).filter(|&k| k != p && k != q) {
+                        let x = a.data[p * n + k];
+                        let y = a.data[q * n + k];
+                        a.data[p * n + k] = c * x - s * y;
+                        a.data[q * n + k] = s * x + c * y;
+                        a.data[k * n + p] = a.data[p * n + k];
+                        a.data[k * n + q] = a.data[q * n + k];
+                    }
+                    a[(p, p)] -= t * apq;
+                    a[(q, q)] += t * apq;
+                    a[(p, q)] = T::zero();
+                    a[(q, p)] = T::zero();
+                    rotate_rows(&mut vt, p, q, c, s);
+                }
+            }
+            if !rotated {
+                converged = true;
+                break;
+            }
+            sweeps += 1;
+        }
+        if !converged {
+            return Err(Error::Numerical(format!("symmetric_eigen did not converge in {} sweeps", MAX_SWEEPS)));
+        }
+        let values: Vec<T> = (0..n).map(|i| a[(i, i)]).collect();
+        let order = sort_descending(&values);
+        let mut eigenvectors = Matrix::new(n, n);
+        for (new, &old) in order.iter().enumerate() {
+            eigenvectors.col_mut(new)?.assign(vt.row(old)?.t())?;
+        }
+        Ok((SymmetricEigen {eigenvalues: order.iter().map(|&i| values[i]).collect(), eigenvectors}, sweeps))
+    }
+
+    /// One-sided Jacobi singular value decomposition.
+    pub fn svd(&self) -> Result<Svd<T>> {
+        if self.rows < self.cols {
+            let Svd {u, singular_values, vt} = Matrix::transpose(self).svd()?;
+            return Ok(Svd {u: Matrix::transpose(&vt), singular_values, vt: Matrix::transpose(&u)});
+        }
+        check_finite(self, "svd")?;
+        let n = self.cols;
+        let mut u = self.clone();
+        let mut v: Matrix<T> = Matrix::identity(n);
+        let two = T::from_f64(2.0);
+        let mut converged = false;
+        for _ in 0..MAX_SWEEPS {
+            let mut rotated = false;
+            for p in 0..n {
+                for q in p + 1..n {
+                    let alpha: T = u.col(p)?.iter().map(|x| x * x).sum();
+                    let beta: T = u.col(q)?.iter().map(|x| x * x).sum();
+                    let gamma: T = u.col(p)?.iter().zip(u.col(q)?.iter()).map(|(x, y)| x * y).sum();
+                    if gamma == T::zero() || gamma.abs() <= T::epsilon() * (alpha * beta).sqrt() {
+                        continue;
+                    }
+                    rotated = true;
+                    let zeta = (beta - alpha) / (two * gamma);
+                    let t = sign(zeta) / (zeta.abs() + (zeta * zeta + T::one()).sqrt());
+                    let c = T::one() / (t * t + T::one()).sqrt();
+                    let s = t * c;
+                    rotate_columns(&mut u, p, q, c, s);
+                    rotate_columns(&mut v, p, q, c, s);
+                }
+            }
+            if !rotated {
+                converged = true;
+                break;
+            }
+        }
+        if !converged {
+            return Err(Error::Numerical(format!("svd did not converge in {} sweeps", MAX_SWEEPS)));
+        }
+        let norms: Vec<T> = (0..n).map(|j| u.col(j).map(|col| col.iter().map(|x| x * x).sum::<T>().sqrt())).collect::<Result<_>>()?;
+        let order = sort_descending(&norms);
+        let mut sorted_u = Matrix::new(self.rows, n);
+        let mut vt = Matrix::new(n, n);
+        for (new, &old) in order.iter().enumerate() {
+            let norm = norms[old];
+            if norm > T::zero() {
+                sorted_u.col_mut(new)?.assign(u.col(old)?)?;
+                sorted_u.col_mut(new)?.map_inplace(|x| x / norm);
+            }
+            vt.row_mut(new)?.assign(v.col(old)?.t())?;
+        }
+        Ok(Svd {u: sorted_u, singular_values: order.iter().map(|&i| norms[i]).collect(), vt})
+    }
+}
+
+#[cfg(test)]
+mod test_linalg {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn assert_close(a: &Matrix, b: &Matrix) {
+        assert_eq!(a.get_shape(), b.get_shape());
+        for (x, y) in a.get_data().iter().zip(b.get_data().iter()) {
+            assert!((x - y).abs() < 1e-9, "{}\n!=\n{}", a, b);
+        }
+    }
+
+    fn matrix() -> Matrix {
+        Matrix::from_vec(vec![4.0, 2.0, 0.6, 2.0, 5.0, 1.0, 0.6, 1.0, 3.0], 3, 3).unwrap()
+    }
+
+    #[test]
+    fn test_lu() {
+        let a = Matrix::from_vec(vec![0.0, 2.0, 1.0, 1.0, 1.0, 0.0, 3.0, 0.0, 1.0], 3, 3).unwrap();
+        let lu = a.lu().unwrap();
+        let mut pa = Matrix::new(3, 3);
+        for (i, &row) in lu.get_permutation().iter().enumerate() {
+            pa.row_mut(i).unwrap().assign(a.row(row).unwrap()).unwrap();
+        }
+        assert_close(&lu.get_l().matmul(&lu.get_u()).unwrap(), &pa);
+        assert!((a.determinant().unwrap() + 5.0).abs() < 1e-12);
+        assert_close(&a.matmul(&a.inverse().unwrap()).unwrap(), &Matrix::identity(3));
+        let b = Matrix::from_vec(vec![3.0, 2.0, 4.0], 3, 1).unwrap();
+        assert_close(&a.matmul(&a.solve(&b).unwrap()).unwrap(), &b);
+        let singular = Matrix::from_vec(vec![1.0, 2.0, 2.0, 4.0], 2, 2).unwrap();
+        assert!(matches!(singular.inverse(), Err(Error::Numerical(_))));
+    }
+
+    #[test]
+    fn test_cholesky_and_qr() {
+        let a = matrix();
+        let l = a.cholesky().unwrap();
+        assert_close(&l.matmul(l.t()).unwrap(), &a);
+        assert!(Matrix::from_vec(vec![1.0, 2.0, 2.0, 1.0], 2, 2).unwrap().cholesky().is_err());
+
+        let tall = Matrix::from_vec(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 10.0, 1.0, 0.0, 1.0], 4, 3).unwrap();
+        let Qr {q, r} = tall.qr().unwrap();
+        assert_close(&q.t().matmul(&q).unwrap(), &Matrix::identity(3));
+        assert_close(&q.matmul(&r).unwrap(), &tall);
+        let x = Matrix::from_vec(vec![1.0, -2.0, 0.5], 3, 1).unwrap();
+        assert_close(&tall.least_squares(&tall.matmul(&x).unwrap()).unwrap(), &x);
+
+        let w: Matrix = Matrix::new_orthogonal(3, 5).unwrap();
+        assert_close(&w.matmul(w.t()).unwrap(), &Matrix::identity(3));
+    }
+
+    #[test]
+    fn test_eigen_and_svd() {
+        let a = matrix();
+        let SymmetricEigen {eigenvalues, eigenvectors} = a.symmetric_eigen().unwrap();
+        assert!(eigenvalues.windows(2).all(|w| w[0] >= w[1]));
+        let mut lambda = Matrix::new(3, 3);
+        eigenvalues.iter().enumerate().for_each(|(i, &value)| lambda[(i, i)] = value);
+        assert_close(&a.matmul(&eigenvectors).unwrap(), &eigenvectors.matmul(&lambda).unwrap());
+
+        let wide = Matrix::from_vec(vec![3.0, 2.0, 2.0, 2.0, 3.0, -2.0], 2, 3).unwrap();
+        let Svd {u, singular_values, vt} = wide.svd().unwrap();
+        assert!((singular_values[0] - 5.0).abs() < 1e-9 && (singular_values[1] - 3.0).abs() < 1e-9);
+        let mut s = Matrix::new(2, 2);
+        singular_values.iter().enumerate().for_each(|(i, &value)| s[(i, i)] = value);
+        assert_close(&u.matmul(&s).unwrap().matmul(&vt).unwrap(), &wide);
+
+        let mut invalid = wide.clone();
+        invalid[(1, 2)] = f64::NAN;
+        assert!(matches!(invalid.svd(), Err(Error::Numerical(_))));
+        assert!(matches!(invalid.t().to_matrix().svd(), Err(Error::Numerical(_))));
+        let mut invalid = a.clone();
+        invalid[(0, 0)] = f64::INFINITY;
+        assert!(matches!(invalid.symmetric_eigen(), Err(Error::Numerical(_))));
+    }
+
+    #[test]
+    fn test_eigen_converges_quickly() {
+        let mut diagonal = Matrix::new(100, 100);
+        (0..100).for_each(|i| diagonal[(i, i)] = (i % 7) as f64);
+        let (eigen, sweeps) = diagonal.jacobi_eigen().unwrap();
+        assert_eq!(sweeps, 0);
+        assert_eq!(eigen.eigenvalues[0], 6.0);
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let b = Matrix::from_vec((0..100 * 100).map(|_| rng.gen_range(-1.0..1.0)).collect(), 100, 100).unwrap();
+        let spd = b.matmul(b.t()).unwrap() + Matrix::identity(100);
+        let (SymmetricEigen {eigenvalues, eigenvectors}, sweeps) = spd.jacobi_eigen().unwrap();
+        assert!(sweeps <= 20, "took {} sweeps", sweeps);
+        assert!(eigenvalues.iter().all(|&value| value > 0.0));
+        let mut lambda = Matrix::new(100, 100);
+        eigenvalues.iter().enumerate().for_each(|(i, &value)| lambda[(i, i)] = value);
+        let reconstructed = eigenvectors.matmul(&lambda).unwrap().matmul(eigenvectors.t()).unwrap();
+        for (x, y) in reconstructed.get_data().iter().zip(spd.get_data().iter()) {
+            assert!((x - y).abs() < 1e-8 * y.abs().max(1.0));
+        }
+    }
+}
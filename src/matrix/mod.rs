@@ -0,0 +1,260 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::error::{Error, Result};
+use crate::float::Float;
+
+mod broadcast;
+mod linalg;
+mod ops;
+mod reductions;
+mod view;
+
+pub use linalg::{Lu, Qr, Svd, SymmetricEigen};
+pub use reductions::{Axis, Norm};
+pub use view::{Lanes, MatrixView, MatrixViewMut};
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(try_from = "RawMatrix<T>", bound(deserialize = ""))]
+pub struct Matrix<T: Float = f64> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>
+}
+
+// unchecked form used to validate deserialized matrices
+#[derive(Deserialize)]
+#[serde(bound(deserialize = ""))]
+struct RawMatrix<T: Float> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>
+}
+
+impl<T: Float> TryFrom<RawMatrix<T>> for Matrix<T> {
+    type Error = Error;
+    fn try_from(raw: RawMatrix<T>) -> Result<Matrix<T>> {
+        Matrix::from_vec(raw.data, raw.rows, raw.cols)
+    }
+}
+
+impl<T: Float> Matrix<T> {
+    pub fn new(rows: usize, cols: usize) -> Matrix<T> {
+        Matrix {rows, cols, data: vec![T::zero(); rows*cols]}
+    }
+
+    pub fn get_num_rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn get_num_cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn get_shape(&self) -> [usize; 2] {
+        [self.rows, self.cols]
+    }
+
+    pub fn new_random(rows: usize, cols: usize) -> Matrix<T> {
+        let mut rng = rand::thread_rng();
+        let data  = (0..rows*cols).map(|_| T::from_f64(rng.gen::<f64>() * 2.0 - 1.0)).collect();
+        Matrix{rows, cols, data}
+    }
+
+    pub fn from_vec(data: Vec<T>, rows: usize, cols: usize) -> Result<Matrix<T>> {
+        if data.len() != rows*cols {
+            return Err(Error::InvalidConfiguration(
+                format!("Data length {} must match matrix dimensions [{}, {}]", data.len(), rows, cols)));
+        }
+        Ok(Matrix {rows, cols, data})
+    }
+
+    pub fn from_nested_vec(data: Vec<Vec<T>>) -> Result<Matrix<T>> {
+        let rows = data.len();
+        if rows == 0 {
+            return Err(Error::InvalidConfiguration("Matrix cannot have 0 rows".to_string()));
+        }
+        let mut values = Vec::new();
+        let cols = data[0].len();
+        for column in data.iter() {
+            if column.len() != cols {
+                return Err(Error::shape_mismatch("from_nested_vec", [1, cols], [1, column.len()]));
+            }
+            values.extend(column.iter());
+        }
+        Ok(Matrix{rows, cols, data: values})
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> T {
+        self.data[row*self.cols + col]
+    }
+
+    pub fn get_data(&self) -> &Vec<T> {
+        &self.data
+    }
+
+    pub fn get_data_mut(&mut self) -> &mut Vec<T> {
+        &mut self.data
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: T) -> &mut Matrix<T>{
+        self.data[row*self.cols + col] = value;
+        self
+    }
+
+    pub fn add_matrix(&mut self, other: &Matrix<T>) -> Result<&mut Matrix<T>>{
+        self.zip_assign_broadcast(other, "add_matrix", |a, b| *a += b)?;
+        Ok(self)
+    }
+
+    pub fn sub_matrix(&mut self, other: &Matrix<T>) -> Result<&mut Matrix<T>>{
+        self.zip_assign_broadcast(other, "sub_matrix", |a, b| *a -= b)?;
+        Ok(self)
+    }
+
+    pub fn add_scalar(&mut self, scalar: T) -> &mut Matrix<T> {
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                self.data[i*self.cols + j] += scalar;
+            }
+        }
+        self
+    }
+
+    pub fn sub_scalar(&mut self, scalar: T) -> &mut Matrix<T> {
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                self.data[i*self.cols + j] -= scalar;
+            }
+        }
+        self
+    }
+
+    pub fn mul_scalar(&mut self, scalar: T) -> &mut Matrix<T> {
+        for i in 0..self.rows {
+            for j in 0..self.cols {
+                self.data[i*self.cols + j] *= scalar;
+            }
+        }
+        self
+    }
+
+    pub fn elementwise_mul(&mut self, other: &Matrix<T>) -> Result<&mut Matrix<T>> {
+        self.zip_assign_broadcast(other, "elementwise_mul", |a, b| *a *= b)?;
+        Ok(self)
+    }
+
+    pub fn elementwise_div(&mut self, other: &Matrix<T>) -> Result<&mut Matrix<T>> {
+        self.zip_assign_broadcast(other, "elementwise_div", |a, b| *a /= b)?;
+        Ok(self)
+    }
+
+    pub fn reshape(self, rows: usize, cols: usize) -> Result<Matrix<T>> {
+        Matrix::from_vec(self.data, rows, cols)
+    }
+
+    pub fn transpose(matrix: &Matrix<T>) -> Matrix<T> {
+        matrix.t().to_matrix()
+    }
+
+    pub fn matmul<'a>(&self, other: impl Into<MatrixView<'a, T>>) -> Result<Matrix<T>> {
+        Matrix::mul(self, other)
+    }
+
+    pub fn mul<'a, 'b>(matrix_1: impl Into<MatrixView<'a, T>>, matrix_2: impl Into<MatrixView<'b, T>>) -> Result<Matrix<T>> {
+        let matrix_1 = matrix_1.into();
+        let matrix_2 = matrix_2.into();
+        if matrix_1.get_num_cols() != matrix_2.get_num_rows() {
+            return Err(Error::shape_mismatch("mul", matrix_1.get_shape(), matrix_2.get_shape()));
+        }
+        let mut result = Matrix::new(matrix_1.get_num_rows(), matrix_2.get_num_cols());
+        for row in 0..matrix_1.get_num_rows() {
+            for col in 0..matrix_2.get_num_cols() {
+                let elem = (0..matrix_1.get_num_cols()).map(|i| matrix_1.get(row, i) * matrix_2.get(i, col)).sum();
+                result.set(row, col, elem);
+            }
+        }
+        Ok(result)
+    }
+
+    pub fn convolution_output_size(input_size: [usize; 2], kernel_size: [usize; 2], stride: usize, padding: usize) -> Result<[usize; 2]> {
+        if stride == 0 {
+            return Err(Error::InvalidConfiguration("Convolution stride must be positive".to_string()));
+        }
+        if kernel_size[0] == 0 || kernel_size[1] == 0
+            || kernel_size[0] > input_size[0] + 2*padding || kernel_size[1] > input_size[1] + 2*padding {
+            return Err(Error::shape_mismatch("convolve", input_size, kernel_size));
+        }
+        Ok([(input_size[0] + 2*padding - kernel_size[0])/stride + 1,
+            (input_size[1] + 2*padding - kernel_size[1])/stride + 1])
+    }
+
+    pub fn convolve(matrix: &Matrix<T>, kernel: &Matrix<T>, stride: usize, padding: usize) -> Result<Matrix<T>> {
+        let [result_rows, result_cols] = Matrix::<T>::convolution_output_size(matrix.get_shape(), kernel.get_shape(), stride, padding)?;
+        let mut result = Matrix::new(result_rows, result_cols);
+        for i_result in 0..result.rows {
+            for j_result in 0..result.cols {
+                let mut sum = T::zero();
+                // doesnt include padding
+                let i_start = i_result*stride;
+                let j_start = j_result*stride;
+                for i_kernel in 0..kernel.rows {
+                    for j_kernel in 0..kernel.cols {
+                        if i_start + i_kernel < padding || j_start + j_kernel < padding {
+                            continue;
+                        }
+                        // now it includes padding
+                        let i_matrix = i_start + i_kernel - padding;
+                        let j_matrix = j_start + j_kernel - padding;
+                        if i_matrix >= matrix.rows || j_matrix >= matrix.cols {
+                            continue;
+                        }
+                        sum += matrix.get(i_matrix, j_matrix) * kernel.get(i_kernel, j_kernel);
+                    }
+                }
+                result.set(i_result, j_result, sum);
+            }
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod test_convolutions {
+    use super::*;
+
+    #[test]
+    fn test_basic() {
+        let matrix = Matrix::from_vec((1..10).map(|i| i as f64).collect(), 3, 3).unwrap();
+        let kernel = Matrix::from_vec((10..14).map(|i| i as f64).collect(), 2, 2).unwrap();
+        let result = Matrix::convolve(&matrix, &kernel, 1, 0).unwrap();
+        let expected = Matrix::from_vec(vec![145.0, 191.0, 283.0, 329.0], 2, 2).unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_padding() {
+        let matrix = Matrix::from_vec(vec![5.0, 4.0, 1.0, 2.0, 3.0, 4.0], 2, 3).unwrap();
+        let kernel = Matrix::from_vec(vec![1.0, 2.0, 3.0, 4.0], 2, 2).unwrap();
+        let result = Matrix::convolve(&matrix, &kernel, 1, 1).unwrap();
+        let expected = Matrix::from_vec(vec![20.0, 31.0, 16.0, 3.0, 18.0, 31.0, 31.0, 13.0, 4.0, 8.0, 11.0, 4.0], 3, 4).unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_stride() {
+        let matrix = Matrix::from_vec((0..16).map(|i| i as f64).collect(), 4, 4).unwrap();
+        let kernel = Matrix::from_vec((0..4).map(|i| i as f64).collect(), 2, 2).unwrap();
+        let result = Matrix::convolve(&matrix, &kernel, 2, 0).unwrap();
+        let expected = Matrix::from_vec(vec![24.0, 36.0, 72.0, 84.0], 2, 2).unwrap();
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_f32() {
+        let matrix: Matrix<f32> = Matrix::from_vec((1..10).map(|i| i as f32).collect(), 3, 3).unwrap();
+        let kernel = Matrix::from_vec((10..14).map(|i| i as f32).collect(), 2, 2).unwrap();
+        let result = Matrix::convolve(&matrix, &kernel, 1, 0).unwrap();
+        let expected = Matrix::from_vec(vec![145.0, 191.0, 283.0, 329.0], 2, 2).unwrap();
+        assert_eq!(result, expected);
+    }
+}
@@ -0,0 +1,211 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign};
+use super::Matrix;
+use crate::float::Float;
+
+// The operators broadcast like the fallible methods but panic on incompatible shapes.
+impl<T: Float> Matrix<T> {
+    fn zip_with(&self, other: &Matrix<T>, op: &'static str, f: impl Fn(T, T) -> T) -> Matrix<T> {
+        self.zip_broadcast(other, op, f).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    fn map(&self, f: impl Fn(T) -> T) -> Matrix<T> {
+        Matrix {rows: self.rows, cols: self.cols, data: self.data.iter().map(|&a| f(a)).collect()}
+    }
+}
+
+macro_rules! impl_elementwise_op {
+    ($trait:ident, $method:ident, $op:tt) => {
+        impl<T: Float> $trait<&Matrix<T>> for &Matrix<T> {
+            type Output = Matrix<T>;
+            fn $method(self, other: &Matrix<T>) -> Matrix<T> {
+                self.zip_with(other, stringify!($method), |a, b| a $op b)
+            }
+        }
+
+        impl<T: Float> $trait<Matrix<T>> for &Matrix<T> {
+            type Output = Matrix<T>;
+            fn $method(self, other: Matrix<T>) -> Matrix<T> {
+                self $op &other
+            }
+        }
+
+        impl<T: Float> $trait<&Matrix<T>> for Matrix<T> {
+            type Output = Matrix<T>;
+            fn $method(self, other: &Matrix<T>) -> Matrix<T> {
+                &self $op other
+            }
+        }
+
+        impl<T: Float> $trait<Matrix<T>> for Matrix<T> {
+            type Output = Matrix<T>;
+            fn $method(self, other: Matrix<T>) -> Matrix<T> {
+                &self $op &other
+            }
+        }
+    };
+}
+
+impl_elementwise_op!(Add, add, +);
+impl_elementwise_op!(Sub, sub, -);
+
+macro_rules! impl_elementwise_assign_op {
+    ($trait:ident, $method:ident, $op:tt) => {
+        impl<T: Float> $trait<&Matrix<T>> for Matrix<T> {
+            fn $method(&mut self, other: &Matrix<T>) {
+                self.zip_assign_broadcast(other, stringify!($method), |a, b| *a $op b)
+                    .unwrap_or_else(|error| panic!("{}", error));
+            }
+        }
+
+        impl<T: Float> $trait<Matrix<T>> for Matrix<T> {
+            fn $method(&mut self, other: Matrix<T>) {
+                *self $op &other;
+            }
+        }
+    };
+}
+
+impl_elementwise_assign_op!(AddAssign, add_assign, +=);
+impl_elementwise_assign_op!(SubAssign, sub_assign, -=);
+
+macro_rules! impl_scalar_op {
+    ($trait:ident, $method:ident, $op:tt, $assign_trait:ident, $assign_method:ident, $assign_op:tt) => {
+        impl<T: Float> $trait<T> for &Matrix<T> {
+            type Output = Matrix<T>;
+            fn $method(self, scalar: T) -> Matrix<T> {
+                self.map(|a| a $op scalar)
+            }
+        }
+
+        impl<T: Float> $trait<T> for Matrix<T> {
+            type Output = Matrix<T>;
+            fn $method(mut self, scalar: T) -> Matrix<T> {
+                self $assign_op scalar;
+                self
+            }
+        }
+
+        impl<T: Float> $assign_trait<T> for Matrix<T> {
+            fn $assign_method(&mut self, scalar: T) {
+                self.data.iter_mut().for_each(|a| *a $assign_op scalar);
+            }
+        }
+    };
+}
+
+impl_scalar_op!(Add, add, +, AddAssign, add_assign, +=);
+impl_scalar_op!(Sub, sub, -, SubAssign, sub_assign, -=);
+impl_scalar_op!(Mul, mul, *, MulAssign, mul_assign, *=);
+impl_scalar_op!(Div, div, /, DivAssign, div_assign, /=);
+
+macro_rules! impl_scalar_lhs_mul {
+    ($t:ty) => {
+        impl Mul<Matrix<$t>> for $t {
+            type Output = Matrix<$t>;
+            fn mul(self, matrix: Matrix<$t>) -> Matrix<$t> {
+                matrix * self
+            }
+        }
+
+        impl Mul<&Matrix<$t>> for $t {
+            type Output = Matrix<$t>;
+            fn mul(self, matrix: &Matrix<$t>) -> Matrix<$t> {
+                matrix * self
+            }
+        }
+    };
+}
+
+impl_scalar_lhs_mul!(f32);
+impl_scalar_lhs_mul!(f64);
+
+impl<T: Float> Neg for &Matrix<T> {
+    type Output = Matrix<T>;
+    fn neg(self) -> Matrix<T> {
+        self.map(|a| -a)
+    }
+}
+
+impl<T: Float> Neg for Matrix<T> {
+    type Output = Matrix<T>;
+    fn neg(mut self) -> Matrix<T> {
+        self.data.iter_mut().for_each(|a| *a = -*a);
+        self
+    }
+}
+
+impl<T: Float> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        assert!(row < self.rows && col < self.cols, "Index ({}, {}) out of bounds for [{}, {}] matrix", row, col, self.rows, self.cols);
+        &self.data[row*self.cols + col]
+    }
+}
+
+impl<T: Float> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        assert!(row < self.rows && col < self.cols, "Index ({}, {}) out of bounds for [{}, {}] matrix", row, col, self.rows, self.cols);
+        &mut self.data[row*self.cols + col]
+    }
+}
+
+impl<T: Float> fmt::Display for Matrix<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in 0..self.rows {
+            write!(f, "[")?;
+            for col in 0..self.cols {
+                if col > 0 {
+                    write!(f, ", ")?;
+                }
+                match f.precision() {
+                    Some(precision) => write!(f, "{:.*}", precision, self.get(row, col))?,
+                    None => write!(f, "{}", self.get(row, col))?
+                }
+            }
+            writeln!(f, "]")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_ops {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Matrix::from_vec(vec![1.0, 2.0, 3.0, 4.0], 2, 2).unwrap();
+        let b = Matrix::from_vec(vec![4.0, 3.0, 2.0, 1.0], 2, 2).unwrap();
+        assert_eq!(&a + &b, Matrix::from_vec(vec![5.0; 4], 2, 2).unwrap());
+        assert_eq!(&a - &b, Matrix::from_vec(vec![-3.0, -1.0, 1.0, 3.0], 2, 2).unwrap());
+        assert_eq!(-(&a * 2.0), Matrix::from_vec(vec![-2.0, -4.0, -6.0, -8.0], 2, 2).unwrap());
+        let mut c = a.clone();
+        c += &b;
+        c *= 0.5;
+        assert_eq!(c, Matrix::from_vec(vec![2.5; 4], 2, 2).unwrap());
+    }
+
+    #[test]
+    fn test_index() {
+        let mut a = Matrix::from_vec(vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0], 2, 3).unwrap();
+        assert_eq!(a[(1, 0)], 4.0);
+        a[(0, 2)] = 9.0;
+        assert_eq!(a.get(0, 2), 9.0);
+        assert_eq!(a.to_string(), "[1, 2, 9]\n[4, 5, 6]\n");
+    }
+
+    #[test]
+    fn test_broadcasting_operators() {
+        let a = Matrix::from_vec(vec![1.0, 2.0, 3.0, 4.0], 2, 2).unwrap();
+        let bias = Matrix::from_vec(vec![1.0, -1.0], 1, 2).unwrap();
+        assert_eq!(&a + &bias, Matrix::from_vec(vec![2.0, 1.0, 4.0, 3.0], 2, 2).unwrap());
+        assert_eq!(a.clone() / 2.0 - 0.5, Matrix::from_vec(vec![0.0, 0.5, 1.0, 1.5], 2, 2).unwrap());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_shape_mismatch() {
+        let _ = Matrix::<f64>::new(2, 2) + Matrix::new(2, 3);
+    }
+}
@@ -0,0 +1,199 @@
+use serde::{Deserialize, Serialize};
+use super::Matrix;
+use crate::float::Float;
+
+/// Direction of an axis reduction. `Rows` reduces every row to a single value
+/// and yields a `[rows, 1]` result, `Cols` reduces every column and yields `[1, cols]`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Axis {
+    Rows,
+    Cols
+}
+
+/// Entrywise norms. For a whole matrix `L2` and `Frobenius` coincide.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Norm {
+    L1,
+    L2,
+    Frobenius
+}
+
+fn norm_of<T: Float>(values: impl Iterator<Item = T>, norm: Norm) -> T {
+    match norm {
+        Norm::L1 => values.map(|x| x.abs()).sum(),
+        Norm::L2 | Norm::Frobenius => values.map(|x| x * x).sum::<T>().sqrt()
+    }
+}
+
+fn mean_of<T: Float>(values: impl Iterator<Item = T>) -> T {
+    let (sum, count) = values.fold((T::zero(), 0), |(sum, count), x| (sum + x, count + 1));
+    sum / T::from_usize(count)
+}
+
+fn variance_of<T: Float>(values: impl Iterator<Item = T> + Clone) -> T {
+    let mean = mean_of(values.clone());
+    mean_of(values.map(|x| (x - mean) * (x - mean)))
+}
+
+// index of the first element that compares `better` than every other, ignoring NaNs
+fn arg_best<T: Float>(values: impl Iterator<Item = T>, better: impl Fn(T, T) -> bool) -> Option<usize> {
+    let mut best: Option<(usize, T)> = None;
+    for (index, x) in values.enumerate() {
+        if x.is_nan() {
+            continue;
+        }
+        match best {
+            Some((_, value)) if !better(x, value) => (),
+            _ => best = Some((index, x))
+        }
+    }
+    best.map(|(index, _)| index)
+}
+
+impl<T: Float> Matrix<T> {
+    fn lane(&self, axis: Axis, index: usize) -> impl Iterator<Item = T> + Clone + '_ {
+        let (start, step, len) = match axis {
+            Axis::Rows => (index * self.cols, 1, self.cols),
+            Axis::Cols => (index, self.cols, self.rows)
+        };
+        self.data.iter().skip(start).step_by(step).take(len).copied()
+    }
+
+    fn num_lanes(&self, axis: Axis) -> usize {
+        match axis {
+            Axis::Rows => self.rows,
+            Axis::Cols => self.cols
+        }
+    }
+
+    fn lane_len(&self, axis: Axis) -> usize {
+        match axis {
+            Axis::Rows => self.cols,
+            Axis::Cols => self.rows
+        }
+    }
+
+    fn reduce_axis(&self, axis: Axis, f: impl Fn(&Matrix<T>, usize) -> T) -> Matrix<T> {
+        let data = (0..self.num_lanes(axis)).map(|index| f(self, index)).collect();
+        match axis {
+            Axis::Rows => Matrix {rows: self.rows, cols: 1, data},
+            Axis::Cols => Matrix {rows: 1, cols: self.cols, data}
+        }
+    }
+
+    pub fn sum(&self) -> T {
+        self.data.iter().copied().sum()
+    }
+
+    pub fn mean(&self) -> T {
+        mean_of(self.data.iter().copied())
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.argmax().map(|(row, col)| self.get(row, col))
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.argmin().map(|(row, col)| self.get(row, col))
+    }
+
+    pub fn argmax(&self) -> Option<(usize, usize)> {
+        arg_best(self.data.iter().copied(), |a, b| a > b).map(|index| (index / self.cols, index % self.cols))
+    }
+
+    pub fn argmin(&self) -> Option<(usize, usize)> {
+        arg_best(self.data.iter().copied(), |a, b| a < b).map(|index| (index / self.cols, index % self.cols))
+    }
+
+    pub fn variance(&self) -> T {
+        variance_of(self.data.iter().copied())
+    }
+
+    pub fn std(&self) -> T {
+        self.variance().sqrt()
+    }
+
+    pub fn norm(&self, norm: Norm) -> T {
+        norm_of(self.data.iter().copied(), norm)
+    }
+
+    pub fn sum_axis(&self, axis: Axis) -> Matrix<T> {
+        self.reduce_axis(axis, |m, index| m.lane(axis, index).sum())
+    }
+
+    pub fn mean_axis(&self, axis: Axis) -> Matrix<T> {
+        self.reduce_axis(axis, |m, index| mean_of(m.lane(axis, index)))
+    }
+
+    pub fn max_axis(&self, axis: Axis) -> Option<Matrix<T>> {
+        let indices = self.argmax_axis(axis)?;
+        Some(self.reduce_axis(axis, |m, index| m.lane(axis, index).nth(indices[index]).unwrap()))
+    }
+
+    pub fn min_axis(&self, axis: Axis) -> Option<Matrix<T>> {
+        let indices = self.argmin_axis(axis)?;
+        Some(self.reduce_axis(axis, |m, index| m.lane(axis, index).nth(indices[index]).unwrap()))
+    }
+
+    pub fn argmax_axis(&self, axis: Axis) -> Option<Vec<usize>> {
+        if self.lane_len(axis) == 0 {
+            return None;
+        }
+        (0..self.num_lanes(axis)).map(|index| arg_best(self.lane(axis, index), |a, b| a > b)).collect()
+    }
+
+    pub fn argmin_axis(&self, axis: Axis) -> Option<Vec<usize>> {
+        if self.lane_len(axis) == 0 {
+            return None;
+        }
+        (0..self.num_lanes(axis)).map(|index| arg_best(self.lane(axis, index), |a, b| a < b)).collect()
+    }
+
+    pub fn variance_axis(&self, axis: Axis) -> Matrix<T> {
+        self.reduce_axis(axis, |m, index| variance_of(m.lane(axis, index)))
+    }
+
+    pub fn std_axis(&self, axis: Axis) -> Matrix<T> {
+        self.reduce_axis(axis, |m, index| variance_of(m.lane(axis, index)).sqrt())
+    }
+
+    pub fn norm_axis(&self, norm: Norm, axis: Axis) -> Matrix<T> {
+        self.reduce_axis(axis, |m, index| norm_of(m.lane(axis, index), norm))
+    }
+}
+
+#[cfg(test)]
+mod test_reductions {
+    use super::*;
+
+    fn matrix() -> Matrix {
+        Matrix::from_vec(vec![1.0, 5.0, 3.0, 4.0, 2.0, 6.0], 2, 3).unwrap()
+    }
+
+    #[test]
+    fn test_whole_matrix() {
+        let m = matrix();
+        assert_eq!(m.sum(), 21.0);
+        assert_eq!(m.mean(), 3.5);
+        assert_eq!(m.max(), Some(6.0));
+        assert_eq!(m.argmax(), Some((1, 2)));
+        assert_eq!(m.argmin(), Some((0, 0)));
+        assert!((m.variance() - 35.0 / 12.0).abs() < 1e-12);
+        assert_eq!(m.norm(Norm::L1), 21.0);
+        assert_eq!(m.norm(Norm::Frobenius), 91.0_f64.sqrt());
+        assert_eq!(Matrix::<f64>::new(0, 0).argmax(), None);
+    }
+
+    #[test]
+    fn test_axis() {
+        let m = matrix();
+        assert_eq!(m.sum_axis(Axis::Rows), Matrix::from_vec(vec![9.0, 12.0], 2, 1).unwrap());
+        assert_eq!(m.sum_axis(Axis::Cols), Matrix::from_vec(vec![5.0, 7.0, 9.0], 1, 3).unwrap());
+        assert_eq!(m.argmax_axis(Axis::Rows), Some(vec![1, 2]));
+        assert_eq!(m.argmin_axis(Axis::Cols), Some(vec![0, 1, 0]));
+        assert_eq!(m.max_axis(Axis::Cols), Some(Matrix::from_vec(vec![4.0, 5.0, 6.0], 1, 3).unwrap()));
+        assert_eq!(m.std_axis(Axis::Cols), Matrix::from_vec(vec![1.5, 1.5, 1.5], 1, 3).unwrap());
+        assert_eq!(m.norm_axis(Norm::L1, Axis::Rows), Matrix::from_vec(vec![9.0, 12.0], 2, 1).unwrap());
+    }
+}
@@ -0,0 +1,379 @@
+use std::ops::{Index, IndexMut, Range};
+use super::{Axis, Matrix};
+use crate::error::{Error, Result};
+use crate::float::Float;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Layout {
+    offset: usize,
+    rows: usize,
+    cols: usize,
+    row_stride: usize,
+    col_stride: usize
+}
+
+impl Layout {
+    fn contiguous(rows: usize, cols: usize) -> Layout {
+        Layout {offset: 0, rows, cols, row_stride: cols, col_stride: 1}
+    }
+
+    fn position(&self, row: usize, col: usize) -> usize {
+        assert!(row < self.rows && col < self.cols, "Index ({}, {}) out of bounds for [{}, {}] view", row, col, self.rows, self.cols);
+        self.offset + row*self.row_stride + col*self.col_stride
+    }
+
+    fn slice(&self, rows: Range<usize>, cols: Range<usize>) -> Result<Layout> {
+        if rows.start > rows.end || rows.end > self.rows || cols.start > cols.end || cols.end > self.cols {
+            return Err(Error::InvalidConfiguration(format!("Slice [{:?}, {:?}] out of bounds for [{}, {}] view",
+                                                           rows, cols, self.rows, self.cols)));
+        }
+        let offset = if rows.is_empty() || cols.is_empty() {
+            self.offset
+        } else {
+            self.position(rows.start, cols.start)
+        };
+        Ok(Layout {offset, rows: rows.len(), cols: cols.len(), ..*self})
+    }
+
+    fn row(&self, row: usize) -> Result<Layout> {
+        self.slice(row..row + 1, 0..self.cols)
+    }
+
+    fn col(&self, col: usize) -> Result<Layout> {
+        self.slice(0..self.rows, col..col + 1)
+    }
+
+    fn diag(&self) -> Layout {
+        let len = self.rows.min(self.cols);
+        Layout {offset: self.offset, rows: len, cols: 1, row_stride: self.row_stride + self.col_stride, col_stride: 1}
+    }
+
+    fn t(&self) -> Layout {
+        Layout {offset: self.offset, rows: self.cols, cols: self.rows, row_stride: self.col_stride, col_stride: self.row_stride}
+    }
+}
+
+/// Borrowed, possibly strided window into the data of a `Matrix`.
+#[derive(Clone, Copy, Debug)]
+pub struct MatrixView<'a, T: Float = f64> {
+    data: &'a [T],
+    layout: Layout
+}
+
+/// Mutable counterpart of `MatrixView`.
+#[derive(Debug)]
+pub struct MatrixViewMut<'a, T: Float = f64> {
+    data: &'a mut [T],
+    layout: Layout
+}
+
+impl<'a, T: Float> MatrixView<'a, T> {
+    pub fn get_num_rows(&self) -> usize {
+        self.layout.rows
+    }
+
+    pub fn get_num_cols(&self) -> usize {
+        self.layout.cols
+    }
+
+    pub fn get_shape(&self) -> [usize; 2] {
+        [self.layout.rows, self.layout.cols]
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> T {
+        self.data[self.layout.position(row, col)]
+    }
+
+    pub fn row(&self, row: usize) -> Result<MatrixView<'a, T>> {
+        Ok(MatrixView {data: self.data, layout: self.layout.row(row)?})
+    }
+
+    pub fn col(&self, col: usize) -> Result<MatrixView<'a, T>> {
+        Ok(MatrixView {data: self.data, layout: self.layout.col(col)?})
+    }
+
+    pub fn slice(&self, rows: Range<usize>, cols: Range<usize>) -> Result<MatrixView<'a, T>> {
+        Ok(MatrixView {data: self.data, layout: self.layout.slice(rows, cols)?})
+    }
+
+    /// Main diagonal as a column view.
+    pub fn diag(&self) -> MatrixView<'a, T> {
+        MatrixView {data: self.data, layout: self.layout.diag()}
+    }
+
+    /// Transposed view; no data is copied.
+    pub fn t(&self) -> MatrixView<'a, T> {
+        MatrixView {data: self.data, layout: self.layout.t()}
+    }
+
+    pub fn rows(&self) -> Lanes<'a, T> {
+        Lanes {view: *self, axis: Axis::Rows, index: 0}
+    }
+
+    pub fn cols(&self) -> Lanes<'a, T> {
+        Lanes {view: *self, axis: Axis::Cols, index: 0}
+    }
+
+    /// Elements in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = T> + 'a {
+        let view = *self;
+        (0..view.layout.rows).flat_map(move |row| (0..view.layout.cols).map(move |col| view.get(row, col)))
+    }
+
+    pub fn to_matrix(&self) -> Matrix<T> {
+        Matrix {rows: self.layout.rows, cols: self.layout.cols, data: self.iter().collect()}
+    }
+
+    pub fn matmul<'b>(&self, other: impl Into<MatrixView<'b, T>>) -> Result<Matrix<T>> {
+        Matrix::mul(*self, other)
+    }
+}
+
+impl<'a, T: Float> MatrixViewMut<'a, T> {
+    pub fn get_num_rows(&self) -> usize {
+        self.layout.rows
+    }
+
+    pub fn get_num_cols(&self) -> usize {
+        self.layout.cols
+    }
+
+    pub fn get_shape(&self) -> [usize; 2] {
+        [self.layout.rows, self.layout.cols]
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> T {
+        self.data[self.layout.position(row, col)]
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, value: T) -> &mut MatrixViewMut<'a, T> {
+        self.data[self.layout.position(row, col)] = value;
+        self
+    }
+
+    pub fn view(&self) -> MatrixView<'_, T> {
+        MatrixView {data: self.data, layout: self.layout}
+    }
+
+    pub fn row_mut(&mut self, row: usize) -> Result<MatrixViewMut<'_, T>> {
+        Ok(MatrixViewMut {layout: self.layout.row(row)?, data: self.data})
+    }
+
+    pub fn col_mut(&mut self, col: usize) -> Result<MatrixViewMut<'_, T>> {
+        Ok(MatrixViewMut {layout: self.layout.col(col)?, data: self.data})
+    }
+
+    pub fn slice_mut(&mut self, rows: Range<usize>, cols: Range<usize>) -> Result<MatrixViewMut<'_, T>> {
+        Ok(MatrixViewMut {layout: self.layout.slice(rows, cols)?, data: self.data})
+    }
+
+    pub fn diag_mut(&mut self) -> MatrixViewMut<'_, T> {
+        MatrixViewMut {layout: self.layout.diag(), data: self.data}
+    }
+
+    pub fn t_mut(&mut self) -> MatrixViewMut<'_, T> {
+        MatrixViewMut {layout: self.layout.t(), data: self.data}
+    }
+
+    pub fn fill(&mut self, value: T) {
+        self.map_inplace(|_| value);
+    }
+
+    pub fn map_inplace(&mut self, f: impl Fn(T) -> T) {
+        for row in 0..self.layout.rows {
+            for col in 0..self.layout.cols {
+                let position = self.layout.position(row, col);
+                self.data[position] = f(self.data[position]);
+            }
+        }
+    }
+
+    pub fn assign<'b>(&mut self, other: impl Into<MatrixView<'b, T>>) -> Result<()> {
+        let other = other.into();
+        if other.get_shape() != self.get_shape() {
+            return Err(Error::shape_mismatch("assign", self.get_shape(), other.get_shape()));
+        }
+        for row in 0..self.layout.rows {
+            for col in 0..self.layout.cols {
+                let position = self.layout.position(row, col);
+                self.data[position] = other.get(row, col);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T: Float> Index<(usize, usize)> for MatrixView<'a, T> {
+    type Output = T;
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        &self.data[self.layout.position(row, col)]
+    }
+}
+
+impl<'a, T: Float> Index<(usize, usize)> for MatrixViewMut<'a, T> {
+    type Output = T;
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        &self.data[self.layout.position(row, col)]
+    }
+}
+
+impl<'a, T: Float> IndexMut<(usize, usize)> for MatrixViewMut<'a, T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        &mut self.data[self.layout.position(row, col)]
+    }
+}
+
+impl<'a, T: Float> From<&'a Matrix<T>> for MatrixView<'a, T> {
+    fn from(matrix: &'a Matrix<T>) -> MatrixView<'a, T> {
+        matrix.view()
+    }
+}
+
+impl<'a, T: Float> From<&'a MatrixView<'a, T>> for MatrixView<'a, T> {
+    fn from(view: &'a MatrixView<'a, T>) -> MatrixView<'a, T> {
+        *view
+    }
+}
+
+/// Iterator over the rows or columns of a view.
+#[derive(Clone, Debug)]
+pub struct Lanes<'a, T: Float = f64> {
+    view: MatrixView<'a, T>,
+    axis: Axis,
+    index: usize
+}
+
+impl<'a, T: Float> Iterator for Lanes<'a, T> {
+    type Item = MatrixView<'a, T>;
+
+    fn next(&mut self) -> Option<MatrixView<'a, T>> {
+        let lane = match self.axis {
+            Axis::Rows => self.view.row(self.index),
+            Axis::Cols => self.view.col(self.index)
+        }.ok()?;
+        self.index += 1;
+        Some(lane)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let total = match self.axis {
+            Axis::Rows => self.view.layout.rows,
+            Axis::Cols => self.view.layout.cols
+        };
+        let remaining = total - self.index.min(total);
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a, T: Float> ExactSizeIterator for Lanes<'a, T> {}
+
+impl<T: Float> Matrix<T> {
+    pub fn view(&self) -> MatrixView<'_, T> {
+        MatrixView {data: &self.data, layout: Layout::contiguous(self.rows, self.cols)}
+    }
+
+    pub fn view_mut(&mut self) -> MatrixViewMut<'_, T> {
+        MatrixViewMut {layout: Layout::contiguous(self.rows, self.cols), data: &mut self.data}
+    }
+
+    pub fn row(&self, row: usize) -> Result<MatrixView<'_, T>> {
+        self.view().row(row)
+    }
+
+    pub fn col(&self, col: usize) -> Result<MatrixView<'_, T>> {
+        self.view().col(col)
+    }
+
+    pub fn slice(&self, rows: Range<usize>, cols: Range<usize>) -> Result<MatrixView<'_, T>> {
+        self.view().slice(rows, cols)
+    }
+
+    pub fn diag(&self) -> MatrixView<'_, T> {
+        self.view().diag()
+    }
+
+    pub fn t(&self) -> MatrixView<'_, T> {
+        self.view().t()
+    }
+
+    pub fn rows(&self) -> Lanes<'_, T> {
+        self.view().rows()
+    }
+
+    pub fn cols(&self) -> Lanes<'_, T> {
+        self.view().cols()
+    }
+
+    pub fn row_mut(&mut self, row: usize) -> Result<MatrixViewMut<'_, T>> {
+        let layout = Layout::contiguous(self.rows, self.cols).row(row)?;
+        Ok(MatrixViewMut {layout, data: &mut self.data})
+    }
+
+    pub fn col_mut(&mut self, col: usize) -> Result<MatrixViewMut<'_, T>> {
+        let layout = Layout::contiguous(self.rows, self.cols).col(col)?;
+        Ok(MatrixViewMut {layout, data: &mut self.data})
+    }
+
+    pub fn slice_mut(&mut self, rows: Range<usize>, cols: Range<usize>) -> Result<MatrixViewMut<'_, T>> {
+        let layout = Layout::contiguous(self.rows, self.cols).slice(rows, cols)?;
+        Ok(MatrixViewMut {layout, data: &mut self.data})
+    }
+
+    pub fn diag_mut(&mut self) -> MatrixViewMut<'_, T> {
+        let layout = Layout::contiguous(self.rows, self.cols).diag();
+        MatrixViewMut {layout, data: &mut self.data}
+    }
+}
+
+#[cfg(test)]
+mod test_views {
+    use super::*;
+
+    fn matrix() -> Matrix {
+        Matrix::from_vec((0..12).map(|i| i as f64).collect(), 3, 4).unwrap()
+    }
+
+    #[test]
+    fn test_slicing() {
+        let m = matrix();
+        assert_eq!(m.row(1).unwrap().to_matrix(), Matrix::from_vec(vec![4.0, 5.0, 6.0, 7.0], 1, 4).unwrap());
+        assert_eq!(m.col(2).unwrap().to_matrix(), Matrix::from_vec(vec![2.0, 6.0, 10.0], 3, 1).unwrap());
+        let block = m.slice(1..3, 1..3).unwrap();
+        assert_eq!(block.to_matrix(), Matrix::from_vec(vec![5.0, 6.0, 9.0, 10.0], 2, 2).unwrap());
+        assert_eq!(block.t().get(0, 1), 9.0);
+        assert_eq!(m.diag().to_matrix(), Matrix::from_vec(vec![0.0, 5.0, 10.0], 3, 1).unwrap());
+        assert_eq!(block.diag().iter().collect::<Vec<_>>(), vec![5.0, 10.0]);
+        assert!(m.slice(0..4, 0..1).is_err());
+    }
+
+    #[test]
+    fn test_iterators() {
+        let m = matrix();
+        let row_sums: Vec<f64> = m.rows().map(|row| row.iter().sum()).collect();
+        assert_eq!(row_sums, vec![6.0, 22.0, 38.0]);
+        assert_eq!(m.cols().len(), 4);
+        assert_eq!(m.t().rows().next().unwrap().iter().collect::<Vec<_>>(), vec![0.0, 4.0, 8.0]);
+    }
+
+    #[test]
+    fn test_matmul_with_views() {
+        let m = matrix();
+        let gram = Matrix::mul(&m, m.t()).unwrap();
+        assert_eq!(gram, Matrix::mul(&m, &Matrix::transpose(&m)).unwrap());
+        let block = m.slice(0..2, 0..2).unwrap();
+        assert_eq!(block.matmul(block.t()).unwrap(), Matrix::from_vec(vec![1.0, 5.0, 5.0, 41.0], 2, 2).unwrap());
+    }
+
+    #[test]
+    fn test_mutable_views() {
+        let mut m = matrix();
+        m.col_mut(0).unwrap().fill(-1.0);
+        m.diag_mut().map_inplace(|x| x * 10.0);
+        let mut block = m.slice_mut(1..3, 2..4).unwrap();
+        block.t_mut()[(1, 0)] = 100.0;
+        assert_eq!(m.get(0, 0), -10.0);
+        assert_eq!(m.get(2, 0), -1.0);
+        assert_eq!(m.get(1, 1), 50.0);
+        assert_eq!(m.get(1, 3), 100.0);
+    }
+}
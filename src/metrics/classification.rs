@@ -0,0 +1,403 @@
+use std::fmt;
+use crate::error::{Error, Result};
+use crate::float::Float;
+use crate::matrix::Matrix;
+use crate::metrics::{check_predictions, Metric};
+
+/// Class predicted by an output: the index of the largest element, or for a
+/// single output, class 1 when it is at least 0.5.
+pub fn predicted_class<T: Float>(output: &Matrix<T>) -> usize {
+    let data = output.get_data();
+    if data.len() == 1 {
+        return if data[0] >= T::from_f64(0.5) {1} else {0};
+    }
+    let (row, col) = output.argmax().unwrap_or((0, 0));
+    row * output.get_num_cols() + col
+}
+
+// Per-class scores, reading a single output `p` as the two classes `[1 - p, p]`.
+fn class_scores<T: Float>(output: &Matrix<T>) -> Vec<T> {
+    let data = output.get_data();
+    if data.len() == 1 {
+        return vec![T::one() - data[0], data[0]];
+    }
+    data.clone()
+}
+
+fn class_count<T: Float>(output: &Matrix<T>) -> usize {
+    output.get_data().len().max(2)
+}
+
+fn ratio(numerator: usize, denominator: usize) -> f64 {
+    if denominator == 0 {0.0} else {numerator as f64 / denominator as f64}
+}
+
+fn f1(precision: f64, recall: f64) -> f64 {
+    if precision + recall == 0.0 {0.0} else {2.0 * precision * recall / (precision + recall)}
+}
+
+pub struct Accuracy;
+
+impl<T: Float> Metric<T> for Accuracy {
+    fn name(&self) -> String {
+        "accuracy".to_string()
+    }
+    fn compute(&self, predictions: &[Matrix<T>], targets: &[Matrix<T>]) -> Result<T> {
+        check_predictions(predictions, targets)?;
+        let correct = predictions.iter().zip(targets.iter())
+            .filter(|(prediction, target)| predicted_class(prediction) == predicted_class(target))
+            .count();
+        Ok(T::from_f64(ratio(correct, predictions.len())))
+    }
+}
+
+/// Fraction of samples whose target class is among the `k` highest scores.
+/// A single output `p` is scored as the two classes `[1 - p, p]`.
+pub struct TopKAccuracy {
+    pub k: usize
+}
+
+impl<T: Float> Metric<T> for TopKAccuracy {
+    fn name(&self) -> String {
+        format!("top_{}_accuracy", self.k)
+    }
+    fn compute(&self, predictions: &[Matrix<T>], targets: &[Matrix<T>]) -> Result<T> {
+        check_predictions(predictions, targets)?;
+        if self.k == 0 {
+            return Err(Error::InvalidConfiguration("k must be positive for top-k accuracy".to_string()));
+        }
+        let correct = predictions.iter().zip(targets.iter()).filter(|(prediction, target)| {
+            let scores = class_scores(prediction);
+            let score = scores[predicted_class(target)];
+            scores.iter().filter(|&&x| x > score).count() < self.k
+        }).count();
+        Ok(T::from_f64(ratio(correct, predictions.len())))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Average {
+    /// Unweighted mean of the per-class scores.
+    Macro,
+    /// Score of the pooled true/false positive counts over all classes.
+    Micro
+}
+
+/// Counts of `(actual, predicted)` class pairs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfusionMatrix {
+    num_classes: usize,
+    counts: Vec<usize>
+}
+
+impl ConfusionMatrix {
+    pub fn new<T: Float>(predictions: &[Matrix<T>], targets: &[Matrix<T>]) -> Result<ConfusionMatrix> {
+        check_predictions(predictions, targets)?;
+        let num_classes = class_count(&predictions[0]);
+        let mut counts = vec![0; num_classes * num_classes];
+        for (prediction, target) in predictions.iter().zip(targets.iter()) {
+            if class_count(prediction) != num_classes {
+                return Err(Error::shape_mismatch("ConfusionMatrix::new", predictions[0].get_shape(), prediction.get_shape()));
+            }
+            counts[predicted_class(target) * num_classes + predicted_class(prediction)] += 1;
+        }
+        Ok(ConfusionMatrix {num_classes, counts})
+    }
+
+    pub fn get_num_classes(&self) -> usize {
+        self.num_classes
+    }
+
+    pub fn get(&self, actual: usize, predicted: usize) -> usize {
+        self.counts[actual * self.num_classes + predicted]
+    }
+
+    pub fn get_total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    fn true_positives(&self, class: usize) -> usize {
+        self.get(class, class)
+    }
+
+    fn predicted(&self, class: usize) -> usize {
+        (0..self.num_classes).map(|actual| self.get(actual, class)).sum()
+    }
+
+    /// Number of samples whose actual class is `class`.
+    pub fn support(&self, class: usize) -> usize {
+        (0..self.num_classes).map(|predicted| self.get(class, predicted)).sum()
+    }
+
+    pub fn accuracy(&self) -> f64 {
+        ratio((0..self.num_classes).map(|class| self.true_positives(class)).sum(), self.get_total())
+    }
+
+    pub fn precision(&self, class: usize) -> f64 {
+        ratio(self.true_positives(class), self.predicted(class))
+    }
+
+    pub fn recall(&self, class: usize) -> f64 {
+        ratio(self.true_positives(class), self.support(class))
+    }
+
+    pub fn f1(&self, class: usize) -> f64 {
+        f1(self.precision(class), self.recall(class))
+    }
+
+    fn macro_average(&self, score: impl Fn(usize) -> f64) -> f64 {
+        (0..self.num_classes).map(score).sum::<f64>() / self.num_classes as f64
+    }
+
+    pub fn average_precision(&self, average: Average) -> f64 {
+        match average {
+            Average::Macro => self.macro_average(|class| self.precision(class)),
+            Average::Micro => self.accuracy()
+        }
+    }
+
+    pub fn average_recall(&self, average: Average) -> f64 {
+        match average {
+            Average::Macro => self.macro_average(|class| self.recall(class)),
+            Average::Micro => self.accuracy()
+        }
+    }
+
+    pub fn average_f1(&self, average: Average) -> f64 {
+        match average {
+            Average::Macro => self.macro_average(|class| self.f1(class)),
+            Average::Micro => f1(self.average_precision(average), self.average_recall(average))
+        }
+    }
+}
+
+impl fmt::Display for ConfusionMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let width = self.counts.iter().max().unwrap_or(&0).to_string().len().max(self.num_classes.to_string().len());
+        write!(f, "{:>w$}", "", w = width + 2)?;
+        for predicted in 0..self.num_classes {
+            write!(f, " {:>w$}", predicted, w = width)?;
+        }
+        writeln!(f)?;
+        for actual in 0..self.num_classes {
+            write!(f, "{:>w$} |", actual, w = width)?;
+            for predicted in 0..self.num_classes {
+                write!(f, " {:>w$}", self.get(actual, predicted), w = width)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+macro_rules! averaged_metric {
+    ($metric:ident, $name:literal, $method:ident) => {
+        pub struct $metric {
+            pub average: Average
+        }
+
+        impl<T: Float> Metric<T> for $metric {
+            fn name(&self) -> String {
+                match self.average {
+                    Average::Macro => concat!($name, "_macro").to_string(),
+                    Average::Micro => concat!($name, "_micro").to_string()
+                }
+            }
+            fn compute(&self, predictions: &[Matrix<T>], targets: &[Matrix<T>]) -> Result<T> {
+                Ok(T::from_f64(ConfusionMatrix::new(predictions, targets)?.$method(self.average)))
+            }
+        }
+    };
+}
+
+averaged_metric!(Precision, "precision", average_precision);
+averaged_metric!(Recall, "recall", average_recall);
+averaged_metric!(F1Score, "f1", average_f1);
+
+/// Receiver operating characteristic of a binary classifier. Points are ordered
+/// by decreasing threshold, starting at `(0, 0)` with an infinite threshold.
+#[derive(Clone, Debug)]
+pub struct RocCurve {
+    pub false_positive_rate: Vec<f64>,
+    pub true_positive_rate: Vec<f64>,
+    pub thresholds: Vec<f64>
+}
+
+impl RocCurve {
+    /// Uses the single output, or the second of two outputs, as the positive class score.
+    pub fn new<T: Float>(predictions: &[Matrix<T>], targets: &[Matrix<T>]) -> Result<RocCurve> {
+        check_predictions(predictions, targets)?;
+        let mut scored = Vec::with_capacity(predictions.len());
+        for (prediction, target) in predictions.iter().zip(targets.iter()) {
+            let score = match prediction.get_data().len() {
+                1 | 2 => *prediction.get_data().last().unwrap(),
+                _ => return Err(Error::InvalidConfiguration("ROC curves require binary outputs".to_string()))
+            };
+            scored.push((score.to_f64(), predicted_class(target) == 1));
+        }
+        let positives = scored.iter().filter(|(_, positive)| *positive).count();
+        let negatives = scored.len() - positives;
+        if positives == 0 || negatives == 0 {
+            return Err(Error::InvalidConfiguration("ROC curves require both positive and negative samples".to_string()));
+        }
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let mut curve = RocCurve {false_positive_rate: vec![0.0], true_positive_rate: vec![0.0], thresholds: vec![f64::INFINITY]};
+        let (mut true_positives, mut false_positives) = (0, 0);
+        for (i, &(score, positive)) in scored.iter().enumerate() {
+            if positive {
+                true_positives += 1;
+            } else {
+                false_positives += 1;
+            }
+            if scored.get(i + 1).is_none_or(|next| next.0 != score) {
+                curve.false_positive_rate.push(ratio(false_positives, negatives));
+                curve.true_positive_rate.push(ratio(true_positives, positives));
+                curve.thresholds.push(score);
+            }
+        }
+        Ok(curve)
+    }
+
+    /// Area under the curve by the trapezoidal rule.
+    pub fn auc(&self) -> f64 {
+        let fpr = &self.false_positive_rate;
+        let tpr = &self.true_positive_rate;
+        (1..fpr.len()).map(|i| (fpr[i] - fpr[i-1]) * (tpr[i] + tpr[i-1]) / 2.0).sum()
+    }
+}
+
+pub struct RocAuc;
+
+impl<T: Float> Metric<T> for RocAuc {
+    fn name(&self) -> String {
+        "roc_auc".to_string()
+    }
+    fn compute(&self, predictions: &[Matrix<T>], targets: &[Matrix<T>]) -> Result<T> {
+        Ok(T::from_f64(RocCurve::new(predictions, targets)?.auc()))
+    }
+}
+
+/// Cross-entropy of the predicted probabilities, binary for single outputs and
+/// categorical otherwise. Probabilities are clipped to `[epsilon, 1 - epsilon]`.
+pub struct LogLoss;
+
+impl<T: Float> Metric<T> for LogLoss {
+    fn name(&self) -> String {
+        "log_loss".to_string()
+    }
+    fn compute(&self, predictions: &[Matrix<T>], targets: &[Matrix<T>]) -> Result<T> {
+        check_predictions(predictions, targets)?;
+        let clip = |p: T| p.max(T::epsilon()).min(T::one() - T::epsilon());
+        let mut total = T::zero();
+        for (prediction, target) in predictions.iter().zip(targets.iter()) {
+            let (prediction, target) = (prediction.get_data(), target.get_data());
+            if prediction.len() == 1 {
+                let p = clip(prediction[0]);
+                total -= target[0] * p.ln() + (T::one() - target[0]) * (T::one() - p).ln();
+            } else {
+                total -= prediction.iter().zip(target.iter()).map(|(&p, &t)| t * clip(p).ln()).sum();
+            }
+        }
+        Ok(total / T::from_usize(predictions.len()))
+    }
+}
+
+/// Per-class precision, recall, F1 and support, printed in the familiar tabular layout.
+#[derive(Clone, Debug)]
+pub struct ClassificationReport {
+    confusion_matrix: ConfusionMatrix,
+    class_names: Vec<String>
+}
+
+impl ClassificationReport {
+    pub fn new<T: Float>(predictions: &[Matrix<T>], targets: &[Matrix<T>]) -> Result<ClassificationReport> {
+        let confusion_matrix = ConfusionMatrix::new(predictions, targets)?;
+        let class_names = (0..confusion_matrix.get_num_classes()).map(|class| class.to_string()).collect();
+        Ok(ClassificationReport {confusion_matrix, class_names})
+    }
+
+    pub fn with_class_names(mut self, class_names: Vec<String>) -> Result<ClassificationReport> {
+        if class_names.len() != self.confusion_matrix.get_num_classes() {
+            return Err(Error::InvalidConfiguration(format!("Expected {} class names, got {}",
+                self.confusion_matrix.get_num_classes(), class_names.len())));
+        }
+        self.class_names = class_names;
+        Ok(self)
+    }
+
+    pub fn get_confusion_matrix(&self) -> &ConfusionMatrix {
+        &self.confusion_matrix
+    }
+
+    fn weighted_average(&self, score: impl Fn(usize) -> f64) -> f64 {
+        let cm = &self.confusion_matrix;
+        let total = (0..cm.get_num_classes()).map(|class| score(class) * cm.support(class) as f64).sum::<f64>();
+        total / cm.get_total() as f64
+    }
+}
+
+impl fmt::Display for ClassificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cm = &self.confusion_matrix;
+        let width = self.class_names.iter().map(|name| name.len()).max().unwrap_or(0).max("weighted avg".len());
+        writeln!(f, "{:>w$} {:>9} {:>9} {:>9} {:>9}", "", "precision", "recall", "f1-score", "support", w = width)?;
+        writeln!(f)?;
+        for (class, name) in self.class_names.iter().enumerate() {
+            writeln!(f, "{:>w$} {:>9.2} {:>9.2} {:>9.2} {:>9}", name,
+                cm.precision(class), cm.recall(class), cm.f1(class), cm.support(class), w = width)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "{:>w$} {:>9} {:>9} {:>9.2} {:>9}", "accuracy", "", "", cm.accuracy(), cm.get_total(), w = width)?;
+        writeln!(f, "{:>w$} {:>9.2} {:>9.2} {:>9.2} {:>9}", "macro avg", cm.average_precision(Average::Macro),
+            cm.average_recall(Average::Macro), cm.average_f1(Average::Macro), cm.get_total(), w = width)?;
+        writeln!(f, "{:>w$} {:>9.2} {:>9.2} {:>9.2} {:>9}", "weighted avg", self.weighted_average(|c| cm.precision(c)),
+            self.weighted_average(|c| cm.recall(c)), self.weighted_average(|c| cm.f1(c)), cm.get_total(), w = width)
+    }
+}
+
+#[cfg(test)]
+mod test_classification {
+    use super::*;
+
+    fn rows(data: &[&[f64]]) -> Vec<Matrix> {
+        data.iter().map(|row| Matrix::from_vec(row.to_vec(), 1, row.len()).unwrap()).collect()
+    }
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-12, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_multiclass() {
+        let predictions = rows(&[&[0.7, 0.2, 0.1], &[0.1, 0.5, 0.4], &[0.3, 0.3, 0.4], &[0.6, 0.1, 0.3], &[0.2, 0.7, 0.1]]);
+        let targets = rows(&[&[1.0, 0.0, 0.0], &[0.0, 1.0, 0.0], &[0.0, 1.0, 0.0], &[0.0, 0.0, 1.0], &[0.0, 1.0, 0.0]]);
+        assert_close(Accuracy.compute(&predictions, &targets).unwrap(), 0.6);
+        assert_close(TopKAccuracy {k: 2}.compute(&predictions, &targets).unwrap(), 1.0);
+        let cm = ConfusionMatrix::new(&predictions, &targets).unwrap();
+        assert_eq!((cm.get(1, 1), cm.get(1, 2), cm.get(2, 0)), (2, 1, 1));
+        assert_close(cm.precision(0), 0.5);
+        assert_close(cm.recall(1), 2.0 / 3.0);
+        assert_close(cm.precision(2), 0.0);
+        assert_close(cm.average_recall(Average::Macro), (1.0 + 2.0 / 3.0) / 3.0);
+        assert_close(F1Score {average: Average::Micro}.compute(&predictions, &targets).unwrap(), 0.6);
+        let report = ClassificationReport::new(&predictions, &targets).unwrap().to_string();
+        assert!(report.contains("accuracy"));
+        assert!(report.lines().nth(2).unwrap().ends_with("0.50      1.00      0.67         1"));
+    }
+
+    #[test]
+    fn test_binary() {
+        let predictions = rows(&[&[0.1], &[0.4], &[0.35], &[0.8]]);
+        let targets = rows(&[&[0.0], &[0.0], &[1.0], &[1.0]]);
+        let curve = RocCurve::new(&predictions, &targets).unwrap();
+        assert_eq!(curve.true_positive_rate, vec![0.0, 0.5, 0.5, 1.0, 1.0]);
+        assert_close(curve.auc(), 0.75);
+        assert_close(Accuracy.compute(&predictions, &targets).unwrap(), 0.75);
+        let expected = -(0.9f64.ln() + 0.6f64.ln() + 0.35f64.ln() + 0.8f64.ln()) / 4.0;
+        assert_close(LogLoss.compute(&predictions, &targets).unwrap(), expected);
+        assert!(RocAuc.compute(&predictions, &rows(&[&[1.0], &[1.0], &[1.0], &[1.0]])).is_err());
+        assert_close(TopKAccuracy {k: 1}.compute(&predictions, &targets).unwrap(), 0.75);
+        assert_close(TopKAccuracy {k: 1}.compute(&rows(&[&[0.3]]), &rows(&[&[1.0]])).unwrap(), 0.0);
+        assert_close(TopKAccuracy {k: 2}.compute(&rows(&[&[0.3]]), &rows(&[&[1.0]])).unwrap(), 1.0);
+    }
+}
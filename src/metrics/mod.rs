@@ -0,0 +1,28 @@
+pub mod classification;
+pub mod regression;
+
+use crate::error::{Error, Result};
+use crate::float::Float;
+use crate::matrix::Matrix;
+
+/// Score computed over the predictions for a whole dataset.
+pub trait Metric<T: Float = f64> {
+    fn name(&self) -> String;
+    fn compute(&self, predictions: &[Matrix<T>], targets: &[Matrix<T>]) -> Result<T>;
+}
+
+pub(crate) fn check_predictions<T: Float>(predictions: &[Matrix<T>], targets: &[Matrix<T>]) -> Result<()> {
+    if predictions.len() != targets.len() {
+        return Err(Error::InvalidConfiguration(
+            format!("Number of predictions ({}) and targets ({}) must match", predictions.len(), targets.len())));
+    }
+    if predictions.is_empty() {
+        return Err(Error::InvalidConfiguration("Cannot compute a metric without predictions".to_string()));
+    }
+    for (prediction, target) in predictions.iter().zip(targets.iter()) {
+        if prediction.get_shape() != target.get_shape() {
+            return Err(Error::shape_mismatch("metric", prediction.get_shape(), target.get_shape()));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,261 @@
+use std::fmt;
+use crate::error::{Error, Result};
+use crate::float::Float;
+use crate::matrix::Matrix;
+use crate::metrics::{check_predictions, Metric};
+
+fn mean_error<T: Float>(predictions: &[Matrix<T>], targets: &[Matrix<T>], error: impl Fn(T, T) -> T) -> Result<T> {
+    check_predictions(predictions, targets)?;
+    let mut total = T::zero();
+    let mut count = 0;
+    for (prediction, target) in predictions.iter().zip(targets.iter()) {
+        total += prediction.get_data().iter().zip(target.get_data().iter()).map(|(&p, &t)| error(p, t)).sum();
+        count += prediction.get_data().len();
+    }
+    Ok(total / T::from_usize(count))
+}
+
+/// Averages `score(targets, predictions)` over the output elements, each taken as
+/// a separate regression target.
+fn per_output_score<T: Float>(predictions: &[Matrix<T>], targets: &[Matrix<T>], score: impl Fn(&[T], &[T]) -> T) -> Result<T> {
+    check_predictions(predictions, targets)?;
+    let outputs = predictions[0].get_data().len();
+    let mut total = T::zero();
+    for output in 0..outputs {
+        let mut column_targets = Vec::with_capacity(targets.len());
+        let mut column_predictions = Vec::with_capacity(predictions.len());
+        for (prediction, target) in predictions.iter().zip(targets.iter()) {
+            if prediction.get_data().len() != outputs {
+                return Err(Error::shape_mismatch("metric", predictions[0].get_shape(), prediction.get_shape()));
+            }
+            column_targets.push(target.get_data()[output]);
+            column_predictions.push(prediction.get_data()[output]);
+        }
+        total += score(&column_targets, &column_predictions);
+    }
+    Ok(total / T::from_usize(outputs))
+}
+
+fn mean<T: Float>(values: &[T]) -> T {
+    values.iter().copied().sum::<T>() / T::from_usize(values.len())
+}
+
+fn variance<T: Float>(values: &[T]) -> T {
+    let mean = mean(values);
+    values.iter().map(|&x| (x - mean).powi(2)).sum::<T>() / T::from_usize(values.len())
+}
+
+/// `1 - residual / total`, with the convention that a constant target scores 1
+/// when predicted exactly and 0 otherwise.
+fn fraction_explained<T: Float>(residual: T, total: T) -> T {
+    if total == T::zero() {
+        if residual == T::zero() {T::one()} else {T::zero()}
+    } else {
+        T::one() - residual / total
+    }
+}
+
+/// Mean of the squared errors over every output element.
+pub struct MeanSquaredError;
+
+impl<T: Float> Metric<T> for MeanSquaredError {
+    fn name(&self) -> String {
+        "mse".to_string()
+    }
+    fn compute(&self, predictions: &[Matrix<T>], targets: &[Matrix<T>]) -> Result<T> {
+        mean_error(predictions, targets, |p, t| (p - t).powi(2))
+    }
+}
+
+pub struct RootMeanSquaredError;
+
+impl<T: Float> Metric<T> for RootMeanSquaredError {
+    fn name(&self) -> String {
+        "rmse".to_string()
+    }
+    fn compute(&self, predictions: &[Matrix<T>], targets: &[Matrix<T>]) -> Result<T> {
+        Ok(mean_error(predictions, targets, |p, t| (p - t).powi(2))?.sqrt())
+    }
+}
+
+pub struct MeanAbsoluteError;
+
+impl<T: Float> Metric<T> for MeanAbsoluteError {
+    fn name(&self) -> String {
+        "mae".to_string()
+    }
+    fn compute(&self, predictions: &[Matrix<T>], targets: &[Matrix<T>]) -> Result<T> {
+        mean_error(predictions, targets, |p, t| (p - t).abs())
+    }
+}
+
+/// Mean of `|target - prediction| / |target|` as a fraction, with the denominator
+/// bounded below by machine epsilon.
+pub struct MeanAbsolutePercentageError;
+
+impl<T: Float> Metric<T> for MeanAbsolutePercentageError {
+    fn name(&self) -> String {
+        "mape".to_string()
+    }
+    fn compute(&self, predictions: &[Matrix<T>], targets: &[Matrix<T>]) -> Result<T> {
+        mean_error(predictions, targets, |p, t| (t - p).abs() / t.abs().max(T::epsilon()))
+    }
+}
+
+pub struct MaxError;
+
+impl<T: Float> Metric<T> for MaxError {
+    fn name(&self) -> String {
+        "max_error".to_string()
+    }
+    fn compute(&self, predictions: &[Matrix<T>], targets: &[Matrix<T>]) -> Result<T> {
+        check_predictions(predictions, targets)?;
+        Ok(predictions.iter().zip(targets.iter())
+            .flat_map(|(prediction, target)| prediction.get_data().iter().zip(target.get_data().iter()))
+            .map(|(&p, &t)| (p - t).abs())
+            // unlike `max`, keeps a NaN error so a diverged model does not look finite
+            .fold(T::zero(), |max, error| if error > max || error.is_nan() {error} else {max}))
+    }
+}
+
+/// Coefficient of determination, averaged uniformly over the outputs.
+pub struct R2Score;
+
+impl<T: Float> Metric<T> for R2Score {
+    fn name(&self) -> String {
+        "r2".to_string()
+    }
+    fn compute(&self, predictions: &[Matrix<T>], targets: &[Matrix<T>]) -> Result<T> {
+        per_output_score(predictions, targets, |targets, predictions| {
+            let residual = targets.iter().zip(predictions.iter()).map(|(&t, &p)| (t - p).powi(2)).sum();
+            fraction_explained(residual, variance(targets) * T::from_usize(targets.len()))
+        })
+    }
+}
+
+/// Like `R2Score`, but ignores a constant offset between predictions and targets.
+pub struct ExplainedVariance;
+
+impl<T: Float> Metric<T> for ExplainedVariance {
+    fn name(&self) -> String {
+        "explained_variance".to_string()
+    }
+    fn compute(&self, predictions: &[Matrix<T>], targets: &[Matrix<T>]) -> Result<T> {
+        per_output_score(predictions, targets, |targets, predictions| {
+            let residuals: Vec<T> = targets.iter().zip(predictions.iter()).map(|(&t, &p)| t - p).collect();
+            fraction_explained(variance(&residuals), variance(targets))
+        })
+    }
+}
+
+/// Distribution of the residuals `target - prediction` over every output element.
+#[derive(Clone, Debug)]
+pub struct ResidualSummary<T: Float = f64> {
+    residuals: Vec<T>
+}
+
+impl<T: Float> ResidualSummary<T> {
+    pub fn new(predictions: &[Matrix<T>], targets: &[Matrix<T>]) -> Result<ResidualSummary<T>> {
+        check_predictions(predictions, targets)?;
+        let mut residuals: Vec<T> = predictions.iter().zip(targets.iter())
+            .flat_map(|(prediction, target)| prediction.get_data().iter().zip(target.get_data().iter()))
+            .map(|(&p, &t)| t - p)
+            .collect();
+        if residuals.iter().any(|x| x.is_nan()) {
+            return Err(Error::Numerical("Residuals contain NaN".to_string()));
+        }
+        residuals.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        Ok(ResidualSummary {residuals})
+    }
+
+    /// Residuals in ascending order.
+    pub fn get_residuals(&self) -> &Vec<T> {
+        &self.residuals
+    }
+
+    pub fn mean(&self) -> T {
+        mean(&self.residuals)
+    }
+
+    /// Population standard deviation.
+    pub fn std(&self) -> T {
+        variance(&self.residuals).sqrt()
+    }
+
+    pub fn min(&self) -> T {
+        self.residuals[0]
+    }
+
+    pub fn max(&self) -> T {
+        self.residuals[self.residuals.len() - 1]
+    }
+
+    pub fn median(&self) -> T {
+        self.percentile(50.0)
+    }
+
+    /// Percentile `q` in `[0, 100]`, linearly interpolated between the closest ranks.
+    pub fn percentile(&self, q: f64) -> T {
+        let position = q.clamp(0.0, 100.0) / 100.0 * (self.residuals.len() - 1) as f64;
+        let lower = position.floor() as usize;
+        let upper = position.ceil() as usize;
+        let fraction = T::from_f64(position - lower as f64);
+        self.residuals[lower] + (self.residuals[upper] - self.residuals[lower]) * fraction
+    }
+}
+
+impl<T: Float> fmt::Display for ResidualSummary<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let precision = f.precision().unwrap_or(4);
+        writeln!(f, "count {}", self.residuals.len())?;
+        writeln!(f, "mean  {:.p$}", self.mean(), p = precision)?;
+        writeln!(f, "std   {:.p$}", self.std(), p = precision)?;
+        writeln!(f, "min   {:.p$}", self.min(), p = precision)?;
+        for q in [25.0, 50.0, 75.0] {
+            writeln!(f, "{:<5} {:.p$}", format!("{}%", q), self.percentile(q), p = precision)?;
+        }
+        write!(f, "max   {:.p$}", self.max(), p = precision)
+    }
+}
+
+#[cfg(test)]
+mod test_regression {
+    use super::*;
+
+    fn rows(data: &[f64]) -> Vec<Matrix> {
+        data.iter().map(|&x| Matrix::from_vec(vec![x], 1, 1).unwrap()).collect()
+    }
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-12, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_metrics() {
+        let targets = rows(&[3.0, -0.5, 2.0, 7.0]);
+        let predictions = rows(&[2.5, 0.0, 2.0, 8.0]);
+        assert_close(MeanSquaredError.compute(&predictions, &targets).unwrap(), 0.375);
+        assert_close(RootMeanSquaredError.compute(&predictions, &targets).unwrap(), 0.375f64.sqrt());
+        assert_close(MeanAbsoluteError.compute(&predictions, &targets).unwrap(), 0.5);
+        assert_close(MaxError.compute(&predictions, &targets).unwrap(), 1.0);
+        assert_close(R2Score.compute(&predictions, &targets).unwrap(), 0.9486081370449679);
+        assert_close(ExplainedVariance.compute(&predictions, &targets).unwrap(), 0.9571734475374732);
+        assert_close(MeanAbsolutePercentageError.compute(&predictions, &targets).unwrap(), (0.5 / 3.0 + 1.0 + 0.0 + 1.0 / 7.0) / 4.0);
+        let shifted = rows(&[4.0, 0.5, 3.0, 8.0]);
+        assert_close(ExplainedVariance.compute(&shifted, &targets).unwrap(), 1.0);
+        let diverged = rows(&[2.5, f64::NAN, 2.0, 8.0]);
+        assert!(MaxError.compute(&diverged, &targets).unwrap().is_nan());
+        assert!(MaxError.compute(&targets, &diverged).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_residual_summary() {
+        let summary = ResidualSummary::new(&rows(&[0.0, 0.0, 0.0, 0.0, 0.0]), &rows(&[1.0, -2.0, 3.0, 0.0, 4.0])).unwrap();
+        assert_close(summary.mean(), 1.2);
+        assert_close(summary.median(), 1.0);
+        assert_close(summary.percentile(25.0), 0.0);
+        assert_close(summary.percentile(90.0), 3.6);
+        assert_eq!((summary.min(), summary.max()), (-2.0, 4.0));
+        assert!(summary.to_string().contains("50%   1.0000"));
+    }
+}
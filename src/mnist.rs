@@ -1,3 +1,8 @@
+use std::fs;
+use std::path::Path;
+use crate::dataset::{check_index, one_hot, Dataset};
+use crate::error::{Error, Result};
+use crate::float::Float;
 use crate::matrix::Matrix;
 
 #[cfg(not(windows))]
@@ -16,21 +21,25 @@ macro_rules! get_mnist_file_path {
     };
 }
 
-const TRAIN_IMAGES: &'static [u8] = include_bytes!(get_mnist_file_path!("train-images-60k"));
-const TRAIN_LABELS: &'static [u8] = include_bytes!(get_mnist_file_path!("train-labels-60k"));
-const TEST_IMAGES: &'static [u8] = include_bytes!(get_mnist_file_path!("test-images-10k"));
-const TEST_LABELS: &'static [u8] = include_bytes!(get_mnist_file_path!("test-labels-10k"));
+const TRAIN_IMAGES: &[u8] = include_bytes!(get_mnist_file_path!("train-images-60k"));
+const TRAIN_LABELS: &[u8] = include_bytes!(get_mnist_file_path!("train-labels-60k"));
+const TEST_IMAGES: &[u8] = include_bytes!(get_mnist_file_path!("test-images-10k"));
+const TEST_LABELS: &[u8] = include_bytes!(get_mnist_file_path!("test-labels-10k"));
 
+const IMAGES_MAGIC_NUMBER: usize = 2051;
+const LABELS_MAGIC_NUMBER: usize = 2049;
+pub const NUM_CLASSES: usize = 10;
 
-pub struct MnistDataset {
+
+pub struct MnistDataset<T: Float = f64> {
     num_of_images: usize,
     num_of_rows: usize,
     num_of_cols: usize,
-    images: Vec<Matrix>,
+    images: Vec<Matrix<T>>,
     labels: Vec<u8>
 }
 
-impl MnistDataset {
+impl<T: Float> MnistDataset<T> {
     pub fn get_num_of_images(&self) -> usize {
         self.num_of_images
     }
@@ -43,7 +52,7 @@ impl MnistDataset {
         self.num_of_cols
     }
 
-    pub fn get_images(&self) -> &Vec<Matrix> {
+    pub fn get_images(&self) -> &Vec<Matrix<T>> {
         &self.images
     }
 
@@ -52,25 +61,73 @@ impl MnistDataset {
     }
 }
 
-fn load_dataset(images: &[u8], labels: &[u8]) -> Result<MnistDataset, std::io::Error> {
-    let images: Vec<u8> = images.to_vec();
-    let labels: Vec<u8> = labels.to_vec();
-    let num_of_images: usize = i32::from_be_bytes(images[4..8].try_into().unwrap()).try_into().unwrap();
-    let num_of_rows: usize = i32::from_be_bytes(images[8..12].try_into().unwrap()).try_into().unwrap();
-    let num_of_cols: usize = i32::from_be_bytes(images[12..16].try_into().unwrap()).try_into().unwrap();
-    let bytes_per_image = num_of_rows * num_of_cols;
+/// Yields each image with its label one-hot encoded as a `[1, 10]` row.
+impl<T: Float> Dataset<T> for MnistDataset<T> {
+    fn len(&self) -> usize {
+        self.num_of_images
+    }
+    fn get(&self, index: usize) -> Result<(Matrix<T>, Matrix<T>)> {
+        check_index(index, self.len())?;
+        Ok((self.images[index].clone(), one_hot(self.labels[index] as usize, NUM_CLASSES)?))
+    }
+}
+
+fn read_header(bytes: &[u8], index: usize) -> Result<usize> {
+    let start = 4 * index;
+    let field: [u8; 4] = bytes.get(start..start+4)
+        .ok_or_else(|| Error::Format("IDX header is truncated".to_string()))?
+        .try_into().unwrap();
+    usize::try_from(i32::from_be_bytes(field))
+        .map_err(|_| Error::Format(format!("IDX header field {} is negative", index)))
+}
+
+// images of an IDX image file as [rows, cols] matrices scaled to [0, 1)
+fn parse_images<T: Float>(images: &[u8]) -> Result<(usize, usize, Vec<Matrix<T>>)> {
+    if read_header(images, 0)? != IMAGES_MAGIC_NUMBER {
+        return Err(Error::Format("Invalid magic number in MNIST images".to_string()));
+    }
+    let num_of_images = read_header(images, 1)?;
+    let num_of_rows = read_header(images, 2)?;
+    let num_of_cols = read_header(images, 3)?;
+    let overflow = || Error::Format("MNIST header sizes overflow".to_string());
+    let bytes_per_image = num_of_rows.checked_mul(num_of_cols).ok_or_else(overflow)?;
+    let len = num_of_images.checked_mul(bytes_per_image).and_then(|len| len.checked_add(16)).ok_or_else(overflow)?;
+    if images.len() < len {
+        return Err(Error::Format("MNIST data is truncated".to_string()));
+    }
     let mut image_data = Vec::with_capacity(num_of_images);
-    let mut label_data = Vec::with_capacity(num_of_images);
     for i in 0..num_of_images {
         let image_start: usize = 16 + i * bytes_per_image;
-        let data: Vec<u8> = images[image_start..(image_start+bytes_per_image)].try_into().unwrap();
-        let data: Vec<f64> = data.iter().map(|&x| (x as f64) / 256.0).collect();
-        image_data.push(Matrix::from_vec(data, num_of_rows, num_of_cols));
-        let label: u8 = labels[8+i];
-        label_data.push(label);
+        let data: &[u8] = &images[image_start..(image_start+bytes_per_image)];
+        let data: Vec<T> = data.iter().map(|&x| T::from_f64((x as f64) / 256.0)).collect();
+        image_data.push(Matrix::from_vec(data, num_of_rows, num_of_cols)?);
+    }
+    Ok((num_of_rows, num_of_cols, image_data))
+}
+
+fn parse_labels(labels: &[u8]) -> Result<Vec<u8>> {
+    if read_header(labels, 0)? != LABELS_MAGIC_NUMBER {
+        return Err(Error::Format("Invalid magic number in MNIST labels".to_string()));
+    }
+    let num_of_labels = read_header(labels, 1)?;
+    if labels.len() < 8 + num_of_labels {
+        return Err(Error::Format("MNIST data is truncated".to_string()));
+    }
+    let label_data = labels[8..8 + num_of_labels].to_vec();
+    if let Some(label) = label_data.iter().find(|&&label| label as usize >= NUM_CLASSES) {
+        return Err(Error::Format(format!("Invalid MNIST label {}", label)));
+    }
+    Ok(label_data)
+}
+
+fn load_dataset<T: Float>(images: &[u8], labels: &[u8]) -> Result<MnistDataset<T>> {
+    let (num_of_rows, num_of_cols, image_data) = parse_images(images)?;
+    let label_data = parse_labels(labels)?;
+    if label_data.len() != image_data.len() {
+        return Err(Error::Format("Number of MNIST labels does not match number of images".to_string()));
     }
     Ok(MnistDataset {
-        num_of_images,
+        num_of_images: image_data.len(),
         num_of_rows,
         num_of_cols,
         images: image_data,
@@ -78,10 +135,41 @@ fn load_dataset(images: &[u8], labels: &[u8]) -> Result<MnistDataset, std::io::E
     })
 }
 
-pub fn load_train_dataset() -> Result<MnistDataset, std::io::Error> {
+pub fn load_train_dataset<T: Float>() -> Result<MnistDataset<T>> {
     load_dataset(TRAIN_IMAGES, TRAIN_LABELS)
 }
 
-pub fn load_test_dataset() -> Result<MnistDataset, std::io::Error> {
+pub fn load_test_dataset<T: Float>() -> Result<MnistDataset<T>> {
     load_dataset(TEST_IMAGES, TEST_LABELS)
 }
+
+/// Reads a dataset from IDX image and label files in the MNIST format.
+pub fn load_files<T: Float>(images: impl AsRef<Path>, labels: impl AsRef<Path>) -> Result<MnistDataset<T>> {
+    load_dataset(&fs::read(images)?, &fs::read(labels)?)
+}
+
+/// Reads the images of an IDX image file, scaled to `[0, 1)` like the datasets.
+pub fn load_images<T: Float>(path: impl AsRef<Path>) -> Result<Vec<Matrix<T>>> {
+    Ok(parse_images(&fs::read(path)?)?.2)
+}
+
+#[cfg(test)]
+mod test_mnist {
+    use super::*;
+
+    fn header(fields: &[i32]) -> Vec<u8> {
+        fields.iter().flat_map(|field| field.to_be_bytes()).collect()
+    }
+
+    #[test]
+    fn test_parse_images() {
+        let mut images = header(&[IMAGES_MAGIC_NUMBER as i32, 2, 1, 2]);
+        images.extend([0, 128, 64, 255]);
+        let (rows, cols, data) = parse_images::<f64>(&images).unwrap();
+        assert_eq!((rows, cols, data.len()), (1, 2, 2));
+        assert_eq!(data[1].get(0, 0), 0.25);
+        assert!(matches!(parse_images::<f64>(&images[..images.len() - 1]), Err(Error::Format(_))));
+        let crafted = header(&[IMAGES_MAGIC_NUMBER as i32, i32::MAX, i32::MAX, i32::MAX]);
+        assert!(matches!(parse_images::<f64>(&crafted), Err(Error::Format(message)) if message.contains("overflow")));
+    }
+}
@@ -1,70 +1,643 @@
-use crate::layers::layer_interface::Layer;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::mem;
+use std::path::Path;
+use std::panic;
+use std::thread;
+use serde::{Deserialize, Serialize};
+use crate::builder::NNBuilder;
+use crate::callbacks::{Action, BatchLogs, Callback, EpochLogs, TrainingParams};
+use crate::config::ModelConfig;
+use crate::data_loader::{Batch, DataLoader};
+use crate::dataset::{Dataset, Subset};
+use crate::error::{Error, Result};
+use crate::float::Float;
+use crate::layers::layer_interface::{check_gradients, Layer};
 use crate::matrix::Matrix;
+use crate::metrics::Metric;
+use crate::summary::{LayerSummary, Summary};
+use crate::training::{Evaluation, History, TrainConfig};
 
-pub struct NN{
-    layers: Vec<Box<dyn Layer>>,
-    learning_rate: f64,
+type PredictionsWithTargets<T> = (Vec<Matrix<T>>, Vec<Matrix<T>>);
+
+// contents of a file written by `NN::save`
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+struct ModelFile<T: Float> {
+    config: ModelConfig,
+    weights: Vec<Matrix<T>>
+}
+
+pub struct NN<T: Float = f64>{
+    layers: Vec<Box<dyn Layer<T>>>,
+    learning_rate: T,
     layer_sizes: Vec<[usize; 2]>
 }
 
-fn mse(y_true: &Matrix, y_pred: &Matrix) -> f64 {
+fn mse<T: Float>(y_true: &Matrix<T>, y_pred: &Matrix<T>) -> Result<T> {
+    if y_true.get_shape() != y_pred.get_shape() {
+        return Err(Error::shape_mismatch("mse", y_true.get_shape(), y_pred.get_shape()));
+    }
     let y_true = y_true.get_data();
     let y_pred = y_pred.get_data();
-    y_true.iter().zip(y_pred.iter()).map(|(t, p)| (t-p).powi(2)).sum()
+    Ok(y_true.iter().zip(y_pred.iter()).map(|(&t, &p)| (t-p).powi(2)).sum())
 }
 
-fn mse_derivative(y_true: &mut Matrix, y_pred: &Matrix, size: f64) -> Matrix {
-    y_true.sub_matrix(y_pred).unwrap().mul_scalar(-2.0 / size).clone()
+fn mse_derivative<T: Float>(y_true: &Matrix<T>, y_pred: &Matrix<T>, size: T) -> Matrix<T> {
+    (y_true - y_pred) * (T::from_f64(-2.0) / size)
 }
 
-impl NN{
-    pub fn new(input_size: [usize; 2], learning_rate: f64) -> NN {
+impl<T: Float> NN<T>{
+    pub fn new(input_size: [usize; 2], learning_rate: T) -> NN<T> {
         let layer_sizes = vec![input_size];
         NN {layers: Vec::new(), learning_rate, layer_sizes}
     }
-    pub fn add(&mut self, mut layer: Box<dyn Layer>){
-        layer.as_mut().initialize(*self.layer_sizes.last().unwrap());
+    /// Starts a `NNBuilder` for a network taking `input_size` inputs.
+    pub fn builder(input_size: [usize; 2]) -> NNBuilder<T> {
+        NNBuilder::new(input_size)
+    }
+    pub fn add(&mut self, mut layer: Box<dyn Layer<T>>) -> Result<()>{
+        layer.as_mut().initialize(*self.layer_sizes.last().unwrap())?;
         self.layer_sizes.push(layer.get_size());
         self.layers.push(layer);
+        Ok(())
+    }
+    pub(crate) fn get_layers_mut(&mut self) -> &mut Vec<Box<dyn Layer<T>>> {
+        &mut self.layers
+    }
+    /// Sum of the layers' regularization penalties, included in the training and evaluation loss.
+    pub fn regularization_loss(&self) -> T {
+        self.layers.iter().map(|layer| layer.regularization_loss()).sum()
     }
-    pub fn predict(&mut self, inputs: &Matrix) -> Matrix {
+    /// Shape of the samples the network takes as input.
+    pub fn get_input_size(&self) -> [usize; 2] {
+        self.layer_sizes[0]
+    }
+    pub fn get_output_size(&self) -> [usize; 2] {
+        self.layer_sizes[self.layer_sizes.len() - 1]
+    }
+    /// Copies of every layer's parameters, in layer order.
+    pub fn get_weights(&self) -> Vec<Matrix<T>> {
+        self.layers.iter().flat_map(|layer| layer.parameters()).cloned().collect()
+    }
+    pub fn set_weights(&mut self, weights: Vec<Matrix<T>>) -> Result<()> {
+        let mut parameters: Vec<&mut Matrix<T>> = self.layers.iter_mut().flat_map(|layer| layer.parameters_mut()).collect();
+        if parameters.len() != weights.len() {
+            return Err(Error::InvalidConfiguration(
+                format!("Expected {} weight matrices, got {}", parameters.len(), weights.len())));
+        }
+        for (parameter, weight) in parameters.iter().zip(weights.iter()) {
+            if parameter.get_shape() != weight.get_shape() {
+                return Err(Error::shape_mismatch("NN::set_weights", parameter.get_shape(), weight.get_shape()));
+            }
+        }
+        for (parameter, weight) in parameters.iter_mut().zip(weights) {
+            **parameter = weight;
+        }
+        Ok(())
+    }
+    /// Writes the config and the weights to one JSON file, read back by `NN::load`.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let model = ModelFile {config: self.get_config()?, weights: self.get_weights()};
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(writer, &model)?;
+        Ok(())
+    }
+    pub fn load(path: impl AsRef<Path>) -> Result<NN<T>> {
+        let reader = BufReader::new(File::open(path)?);
+        let model: ModelFile<T> = serde_json::from_reader(reader)?;
+        let mut nn = model.config.build()?;
+        nn.set_weights(model.weights)?;
+        Ok(nn)
+    }
+    /// Layer names, shapes, parameter counts and memory estimates of the network.
+    pub fn get_summary(&self) -> Summary {
+        let element_size = mem::size_of::<T>();
+        let count = |matrices: Vec<&Matrix<T>>| matrices.iter().map(|m| m.get_num_rows() * m.get_num_cols()).sum::<usize>();
+        let layers = self.layers.iter().zip(self.layer_sizes.windows(2)).map(|(layer, sizes)| {
+            let trainable_parameters = count(layer.parameters());
+            let non_trainable_parameters = count(layer.non_trainable_parameters());
+            LayerSummary {
+                name: layer.name(),
+                input_size: sizes[0],
+                output_size: sizes[1],
+                trainable_parameters,
+                non_trainable_parameters,
+                parameter_memory: (trainable_parameters + non_trainable_parameters) * element_size,
+                activation_memory: sizes[1][0] * sizes[1][1] * element_size
+            }
+        }).collect();
+        let input_size = self.layer_sizes[0];
+        Summary {input_size, layers, input_memory: input_size[0] * input_size[1] * element_size}
+    }
+    /// Architecture of the network, to be saved with `ModelConfig::save` and rebuilt
+    /// with `ModelConfig::build`. Fails for layers without a configuration.
+    pub fn get_config(&self) -> Result<ModelConfig> {
+        let layers = self.layers.iter().enumerate().map(|(index, layer)| layer.config().ok_or_else(||
+            Error::InvalidConfiguration(format!("layer {} ({}) cannot be described in a model config", index, layer.name()))))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(ModelConfig {input_size: self.layer_sizes[0], layers, learning_rate: self.learning_rate.to_f64()})
+    }
+    /// Prints the table of `get_summary`.
+    pub fn summary(&self) {
+        println!("{}", self.get_summary());
+    }
+    pub fn save_weights(&self, path: impl AsRef<Path>) -> Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(writer, &self.get_weights())?;
+        Ok(())
+    }
+    pub fn load_weights(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let reader = BufReader::new(File::open(path)?);
+        self.set_weights(serde_json::from_reader(reader)?)
+    }
+    /// Training forward pass, caching each layer's input for `backward`.
+    pub(crate) fn forward(&mut self, inputs: &Matrix<T>) -> Result<Matrix<T>> {
+        let mut layers = self.layers.iter_mut();
+        let Some(first) = layers.next() else {
+            return Ok(inputs.clone());
+        };
+        let mut outputs = first.forward(inputs)?;
+        for layer in layers {
+            outputs = layer.forward(&outputs)?;
+        }
+        Ok(outputs)
+    }
+    /// Stateless inference; a trained network can be shared between threads, e.g. in an `Arc`.
+    pub fn predict(&self, inputs: &Matrix<T>) -> Result<Matrix<T>> {
         let mut outputs = inputs.clone();
-        for layer in self.layers.iter_mut() {
-            outputs = layer.forward(&outputs);
-        }
-        outputs
-    }
-    pub fn train(&mut self, x_train: &Vec<Matrix>, y_train: &Vec<Matrix>, epochs: u64) -> Result<Vec<f64>, String>{
-        if x_train.len() != y_train.len() {
-            return Err("x_train and y_train must have the same length".to_owned());
-        }
-        let mut errors = Vec::new();
-        let input_num_cols: f64 = x_train[0].get_num_cols() as f64;
-        let input_num_rows: f64 = x_train[0].get_num_rows() as f64;
-        let input_size: f64 = input_num_cols * input_num_rows;
-        for epoch in 0..epochs {
-            let mut err = 0.0;
-            for i in 0..x_train.len() {
-                let mut outputs = x_train[i].clone();
-                for layer in self.layers.iter_mut() {
-                    outputs = layer.forward(&outputs);
+        for layer in self.layers.iter() {
+            outputs = layer.infer(&outputs)?;
+        }
+        Ok(outputs)
+    }
+    pub fn train<D: Dataset<T> + ?Sized>(&mut self, dataset: &D, epochs: u64) -> Result<Vec<T>> {
+        Ok(self.fit(dataset, &TrainConfig::new(epochs))?.loss)
+    }
+    /// Trains on the batches produced by `loader`, returning the mean loss per epoch.
+    pub fn train_loader<D: Dataset<T> + ?Sized>(&mut self, loader: &mut DataLoader<T, D>, epochs: u64) -> Result<Vec<T>> {
+        Ok(self.fit_loader(loader, None, &TrainConfig::new(epochs), &mut [], &mut [])?.loss)
+    }
+    pub fn fit<D: Dataset<T> + ?Sized>(&mut self, dataset: &D, config: &TrainConfig<T>) -> Result<History<T>> {
+        self.fit_with_callbacks(dataset, config, &mut [])
+    }
+    /// Trains on one thread, or with more than one `config.get_workers()` like
+    /// `fit_parallel` on replicas rebuilt from `get_config`.
+    pub fn fit_with_callbacks<D: Dataset<T> + ?Sized>(&mut self, dataset: &D, config: &TrainConfig<T>,
+                                                      callbacks: &mut [&mut dyn Callback<T>]) -> Result<History<T>> {
+        match config.get_workers() {
+            0 => Err(Error::InvalidConfiguration("Number of workers must be positive".to_owned())),
+            1 => self.fit_dataset(dataset, config, &mut [], callbacks),
+            workers => {
+                let model = self.get_config().map_err(|error| Error::InvalidConfiguration(format!(
+                    "Training on {} workers rebuilds the network from its config, use NN::fit_parallel instead: {}", workers, error)))?;
+                self.fit_parallel(dataset, config, || model.build(), callbacks)
+            }
+        }
+    }
+    /// Data-parallel `fit_with_callbacks` on `config.get_workers()` threads. `build_fn` is
+    /// called once per worker for a replica of this network's architecture; each replica
+    /// receives the current weights, computes the gradients of its shard of every batch,
+    /// and the summed gradients are applied to this network in one synchronized update.
+    /// Fails before training for layers that do not expose their gradients, such as
+    /// those that only implement `backwards`.
+    pub fn fit_parallel<D, F>(&mut self, dataset: &D, config: &TrainConfig<T>, mut build_fn: F,
+                              callbacks: &mut [&mut dyn Callback<T>]) -> Result<History<T>>
+        where D: Dataset<T> + ?Sized, F: FnMut() -> Result<NN<T>> {
+        if config.get_workers() == 0 {
+            return Err(Error::InvalidConfiguration("Number of workers must be positive".to_owned()));
+        }
+        self.check_backward("NN::fit_parallel")?;
+        let mut replicas = (0..config.get_workers()).map(|_| build_fn()).collect::<Result<Vec<_>>>()?;
+        for replica in replicas.iter_mut() {
+            if replica.layer_sizes != self.layer_sizes {
+                return Err(Error::InvalidConfiguration("Replica architecture does not match the network".to_owned()));
+            }
+            replica.set_weights(self.get_weights())?;
+        }
+        self.fit_dataset(dataset, config, &mut replicas, callbacks)
+    }
+    /// Runs `backward` through every layer on zeros, failing for layers that provide no
+    /// gradients. The gradients are cleared afterwards.
+    fn check_backward(&mut self, operation: &str) -> Result<()> {
+        for (index, layer) in self.layers.iter().enumerate() {
+            check_gradients(layer.as_ref(), index, operation)?;
+        }
+        let [rows, cols] = self.layer_sizes[0];
+        let [output_rows, output_cols] = self.get_output_size();
+        self.forward(&Matrix::new(rows, cols))?;
+        let mut error = Matrix::new(output_rows, output_cols);
+        for (index, layer) in self.layers.iter_mut().enumerate().rev() {
+            error = layer.backward(&error).map_err(|error| Error::InvalidConfiguration(format!(
+                "{} needs gradients from every layer, but layer {} ({}) does not provide them: {}", operation, index, layer.name(), error)))?;
+        }
+        self.layers.iter_mut().for_each(|layer| layer.zero_grad());
+        Ok(())
+    }
+    fn fit_dataset<D: Dataset<T> + ?Sized>(&mut self, dataset: &D, config: &TrainConfig<T>, replicas: &mut [NN<T>],
+                                           callbacks: &mut [&mut dyn Callback<T>]) -> Result<History<T>> {
+        let num_train = config.num_train_samples(dataset.len())?;
+        let train = Subset::new(dataset, (0..num_train).collect())?;
+        let validation = Subset::new(dataset, (num_train..dataset.len()).collect())?;
+        let validation = if validation.is_empty() {None} else {Some(&validation as &dyn Dataset<T>)};
+        self.fit_loader(&mut config.loader(&train)?, validation, config, replicas, callbacks)
+    }
+    /// Trains on `loader`, computing gradients on `replicas` in parallel when there are any.
+    fn fit_loader<D: Dataset<T> + ?Sized>(&mut self, loader: &mut DataLoader<T, D>, validation: Option<&dyn Dataset<T>>,
+                                          config: &TrainConfig<T>, replicas: &mut [NN<T>],
+                                          callbacks: &mut [&mut dyn Callback<T>]) -> Result<History<T>> {
+        if loader.num_batches() == 0 {
+            return Err(Error::InvalidConfiguration("Training data must not be empty".to_owned()));
+        }
+        let accumulation_steps = config.get_accumulation_steps();
+        if accumulation_steps == 0 {
+            return Err(Error::InvalidConfiguration("Gradient accumulation steps must be positive".to_owned()));
+        }
+        let params = TrainingParams {
+            epochs: config.get_epochs(),
+            num_batches: loader.num_batches(),
+            num_samples: loader.get_dataset().len()
+        };
+        for callback in callbacks.iter_mut() {
+            callback.on_train_begin(self, &params)?;
+        }
+        self.layers.iter_mut().for_each(|layer| layer.zero_grad());
+        let mut history = History::default();
+        for epoch in 0..params.epochs {
+            for callback in callbacks.iter_mut() {
+                callback.on_epoch_begin(self, epoch)?;
+            }
+            let mut stop = false;
+            let mut err = T::zero();
+            let mut samples = 0;
+            let mut pending_samples = 0;
+            for (batch_index, batch) in loader.iter().enumerate() {
+                let batch = batch?;
+                for callback in callbacks.iter_mut() {
+                    callback.on_batch_begin(self, epoch, batch_index)?;
                 }
-                let mut y_true = y_train[i].clone();
-                err += mse(&outputs, &y_true);
-
-                let mut error = mse_derivative(&mut y_true, &outputs, input_size);
-                for layer in self.layers.iter_mut().rev() {
-                    error = layer.backwards(&error, self.learning_rate);
+                let batch_err = if replicas.is_empty() {
+                    self.accumulate_batch(&batch.inputs, &batch.targets, Some(self.learning_rate))?
+                } else {
+                    self.accumulate_parallel(replicas, &batch)?
+                };
+                err += batch_err;
+                samples += batch.len();
+                pending_samples += batch.len();
+                let gradient_norm = if (batch_index + 1) % accumulation_steps == 0 || batch_index + 1 == params.num_batches {
+                    let gradient_norm = self.apply_gradients(pending_samples, config)?;
+                    pending_samples = 0;
+                    gradient_norm
+                } else {
+                    None
+                };
+                let logs = BatchLogs {
+                    epoch,
+                    batch: batch_index,
+                    size: batch.len(),
+                    loss: batch_err / T::from_usize(batch.len()),
+                    gradient_norm
+                };
+                for callback in callbacks.iter_mut() {
+                    stop |= callback.on_batch_end(self, &logs)? == Action::Stop;
                 }
+                if stop {
+                    break;
+                }
+            }
+            // gradients of a window cut short by a stopping callback are discarded
+            self.layers.iter_mut().for_each(|layer| layer.zero_grad());
+            let validation = match validation {
+                Some(validation) => Some(self.evaluate(validation, config.get_metrics())?),
+                None => None
+            };
+            let logs = EpochLogs {epoch, loss: err / T::from_usize(samples), validation};
+            history.loss.push(logs.loss);
+            history.validation.extend(logs.validation.clone());
+            for callback in callbacks.iter_mut() {
+                stop |= callback.on_epoch_end(self, &logs)? == Action::Stop;
+            }
+            if stop {
+                break;
+            }
+        }
+        for callback in callbacks.iter_mut() {
+            callback.on_train_end(self, &history)?;
+        }
+        Ok(history)
+    }
+    /// Backpropagates every sample, adding to the layers' gradients. Layers that update
+    /// themselves in `backwards` are given `learning_rate`; replicas pass `None` and go
+    /// through `backward` only. Returns the summed loss.
+    fn accumulate_batch(&mut self, inputs: &[Matrix<T>], targets: &[Matrix<T>], learning_rate: Option<T>) -> Result<T> {
+        let mut loss = self.regularization_loss() * T::from_usize(inputs.len());
+        for (inputs, y_true) in inputs.iter().zip(targets.iter()) {
+            let outputs = self.forward(inputs)?;
+            loss += mse(&outputs, y_true)?;
 
+            let input_size = T::from_usize(inputs.get_num_rows() * inputs.get_num_cols());
+            let mut error = mse_derivative(y_true, &outputs, input_size);
+            for layer in self.layers.iter_mut().rev() {
+                error = match learning_rate {
+                    Some(learning_rate) => layer.backwards(&error, learning_rate)?,
+                    None => layer.backward(&error)?
+                };
+            }
+        }
+        Ok(loss)
+    }
+    /// Splits `batch` into one contiguous shard per replica and backpropagates the shards
+    /// concurrently, then adds the replicas' gradients to this network's in replica order.
+    /// Returns the summed loss.
+    fn accumulate_parallel(&mut self, replicas: &mut [NN<T>], batch: &Batch<T>) -> Result<T> {
+        let weights = self.get_weights();
+        let shard_size = batch.len().div_ceil(replicas.len());
+        let shards = batch.inputs.chunks(shard_size).zip(batch.targets.chunks(shard_size));
+        let losses = thread::scope(|scope| {
+            let workers: Vec<_> = replicas.iter_mut().zip(shards).map(|(replica, (inputs, targets))| {
+                let weights = weights.clone();
+                scope.spawn(move || {
+                    replica.set_weights(weights)?;
+                    replica.accumulate_batch(inputs, targets, None)
+                })
+            }).collect();
+            workers.into_iter()
+                .map(|worker| worker.join().unwrap_or_else(|payload| panic::resume_unwind(payload)))
+                .collect::<Result<Vec<T>>>()
+        })?;
+        for replica in replicas.iter_mut() {
+            let gradients = self.layers.iter_mut().flat_map(|layer| layer.gradients_mut());
+            let replica_gradients = replica.layers.iter().flat_map(|layer| layer.gradients());
+            for (gradient, replica_gradient) in gradients.zip(replica_gradients) {
+                gradient.add_matrix(replica_gradient)?;
             }
-            err = err / f64::try_from(i32::try_from(x_train.len()).unwrap()).unwrap();
-            errors.push(err);
-            if epochs % 100 == 0 {
-                println!("{:?} Error: {:?}", epoch, err);
+            replica.layers.iter_mut().for_each(|layer| layer.zero_grad());
+        }
+        Ok(losses.into_iter().fold(T::zero(), |total, loss| total + loss))
+    }
+    /// Averages the accumulated gradients over `samples`, clips them and applies one
+    /// update followed by the layer constraints. Returns the global gradient norm
+    /// before clipping.
+    fn apply_gradients(&mut self, samples: usize, config: &TrainConfig<T>) -> Result<Option<T>> {
+        let mut gradients: Vec<&mut Matrix<T>> = self.layers.iter_mut().flat_map(|layer| layer.gradients_mut()).collect();
+        let gradient_norm = if gradients.is_empty() {
+            None
+        } else {
+            let scale = T::one() / T::from_usize(samples);
+            gradients.iter_mut().for_each(|gradient| **gradient *= scale);
+            Some(config.get_gradient_clipping().clip(&mut gradients))
+        };
+        for layer in self.layers.iter_mut() {
+            let steps: Vec<Matrix<T>> = layer.gradients().into_iter().map(|gradient| gradient * self.learning_rate).collect();
+            for (parameter, step) in layer.parameters_mut().into_iter().zip(steps) {
+                parameter.sub_matrix(&step)?;
             }
+            layer.apply_constraints()?;
+            layer.zero_grad();
+        }
+        Ok(gradient_norm)
+    }
+    /// Predictions for every sample of `dataset`, returned with the matching targets.
+    pub fn predict_dataset<D: Dataset<T> + ?Sized>(&self, dataset: &D) -> Result<PredictionsWithTargets<T>> {
+        let mut predictions = Vec::with_capacity(dataset.len());
+        let mut targets = Vec::with_capacity(dataset.len());
+        for i in 0..dataset.len() {
+            let (inputs, y_true) = dataset.get(i)?;
+            predictions.push(self.predict(&inputs)?);
+            targets.push(y_true);
+        }
+        Ok((predictions, targets))
+    }
+    /// Mean loss over `dataset` together with the given metrics computed on its predictions.
+    pub fn evaluate<D: Dataset<T> + ?Sized>(&self, dataset: &D, metrics: &[Box<dyn Metric<T>>]) -> Result<Evaluation<T>> {
+        if dataset.is_empty() {
+            return Err(Error::InvalidConfiguration("Evaluation data must not be empty".to_owned()));
+        }
+        let (predictions, targets) = self.predict_dataset(dataset)?;
+        let mut loss = T::zero();
+        for (outputs, y_true) in predictions.iter().zip(targets.iter()) {
+            loss += mse(outputs, y_true)?;
+        }
+        loss /= T::from_usize(dataset.len());
+        loss += self.regularization_loss();
+        let metrics = metrics.iter()
+            .map(|metric| Ok((metric.name(), metric.compute(&predictions, &targets)?)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Evaluation {loss, metrics})
+    }
+}
+
+#[cfg(test)]
+mod test_neural_network {
+    use super::*;
+    use crate::activation_function::Sigmoid;
+    use crate::clipping::{global_norm, GradientClipping};
+    use crate::dataset::VecDataset;
+    use crate::layers::{activation_layer::ActivationLayer, dense_layer::DenseLayer};
+    use crate::metrics::regression::MeanSquaredError;
+    use crate::training::cross_validate;
+    use std::sync::Arc;
+    use std::thread;
 
+    struct Identity;
+
+    impl Layer for Identity {
+        fn forward(&mut self, inputs: &Matrix) -> Result<Matrix> {
+            Ok(inputs.clone())
+        }
+        fn backwards(&mut self, output_error: &Matrix, _learning_rate: f64) -> Result<Matrix> {
+            Ok(output_error.clone())
         }
-        Ok(errors)
+        fn get_size(&self) -> [usize; 2] {
+            [1, 1]
+        }
+    }
+
+    fn build() -> Result<NN> {
+        let mut nn = NN::new([1, 2], 0.5);
+        nn.add(Box::new(DenseLayer::new(3)))?;
+        nn.add(Box::new(ActivationLayer::new(Box::new(Sigmoid{}))))?;
+        nn.add(Box::new(DenseLayer::new(1)))?;
+        Ok(nn)
+    }
+
+    fn dataset() -> VecDataset {
+        let inputs = (0..10).map(|i| Matrix::from_vec(vec![i as f64 / 10.0, 1.0 - i as f64 / 10.0], 1, 2).unwrap()).collect();
+        let targets = (0..10).map(|i| Matrix::from_vec(vec![i as f64 / 10.0], 1, 1).unwrap()).collect();
+        VecDataset::new(inputs, targets).unwrap()
+    }
+
+    #[test]
+    fn test_validation_split() {
+        let mut nn = build().unwrap();
+        let config = TrainConfig::new(5).validation_split(0.2).batch_size(4).shuffle(true).seed(1)
+            .metric(Box::new(MeanSquaredError));
+        let history = nn.fit(&dataset(), &config).unwrap();
+        assert_eq!(history.loss.len(), 5);
+        assert_eq!(history.validation.len(), 5);
+        let validation_mse = history.get_validation_metric("mse").unwrap();
+        for (loss, mse) in history.get_validation_loss().iter().zip(validation_mse.iter()) {
+            assert!((loss - mse).abs() < 1e-12);
+        }
+        assert!(history.get_validation_metric("accuracy").is_none());
+        assert!(nn.fit(&dataset(), &TrainConfig::new(1).validation_split(1.0)).is_err());
+    }
+
+    #[test]
+    fn test_cross_validate() {
+        let config = TrainConfig::new(3).metric(Box::new(MeanSquaredError));
+        let result = cross_validate(5, build, &dataset(), &config).unwrap();
+        assert_eq!(result.folds.len(), 5);
+        let mean = result.mean();
+        let expected = result.folds.iter().map(|fold| fold.loss).sum::<f64>() / 5.0;
+        assert!((mean.loss - expected).abs() < 1e-12);
+        assert!(result.std().get_metric("mse").unwrap() >= 0.0);
+        assert!(cross_validate(11, build, &dataset(), &config).is_err());
+    }
+
+    struct GradientNorms(Vec<f64>);
+
+    impl Callback for GradientNorms {
+        fn on_batch_end(&mut self, _nn: &mut NN, logs: &BatchLogs) -> Result<Action> {
+            self.0.extend(logs.gradient_norm);
+            Ok(Action::Continue)
+        }
+    }
+
+    #[test]
+    fn test_gradient_clipping() {
+        let mut nn = NN::new([1, 2], 0.1);
+        nn.add(Box::new(DenseLayer::new(1))).unwrap();
+        let inputs = vec![Matrix::from_vec(vec![10.0, -10.0], 1, 2).unwrap(); 4];
+        let targets = vec![Matrix::from_vec(vec![100.0], 1, 1).unwrap(); 4];
+        let dataset = VecDataset::new(inputs, targets).unwrap();
+        let before = nn.get_weights();
+        let mut norms = GradientNorms(Vec::new());
+        let config = TrainConfig::new(1).batch_size(4).gradient_clipping(GradientClipping::new().by_global_norm(0.5));
+        nn.fit_with_callbacks(&dataset, &config, &mut [&mut norms]).unwrap();
+        assert_eq!(norms.0.len(), 1);
+        assert!(norms.0[0] > 0.5);
+        let steps: Vec<Matrix> = before.iter().zip(nn.get_weights().iter()).map(|(b, a)| b - a).collect();
+        assert!((global_norm(&steps) - 0.05).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_gradient_accumulation() {
+        let dataset = dataset();
+        let mut accumulated = build().unwrap();
+        let mut full_batch = build().unwrap();
+        full_batch.set_weights(accumulated.get_weights()).unwrap();
+        let accumulated_history = accumulated.fit(&dataset, &TrainConfig::new(3).batch_size(5).accumulation_steps(2)).unwrap();
+        let full_batch_history = full_batch.fit(&dataset, &TrainConfig::new(3).batch_size(10)).unwrap();
+        for (a, b) in accumulated_history.loss.iter().zip(full_batch_history.loss.iter()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        for (a, b) in accumulated.get_weights().iter().zip(full_batch.get_weights().iter()) {
+            for (x, y) in a.get_data().iter().zip(b.get_data().iter()) {
+                assert!((x - y).abs() < 1e-12);
+            }
+        }
+        // 5 batches of 2 are applied after the 2nd and 4th, and the epoch's leftover after the 5th
+        let mut norms = GradientNorms(Vec::new());
+        let config = TrainConfig::new(2).batch_size(2).accumulation_steps(2);
+        accumulated.fit_with_callbacks(&dataset, &config, &mut [&mut norms]).unwrap();
+        assert_eq!(norms.0.len(), 6);
+        assert!(accumulated.fit(&dataset, &TrainConfig::new(1).accumulation_steps(0)).is_err());
+    }
+
+    #[test]
+    fn test_shared_prediction() {
+        let dataset = dataset();
+        let mut nn = build().unwrap();
+        nn.train(&dataset, 5).unwrap();
+        let (expected, _) = nn.predict_dataset(&dataset).unwrap();
+        let nn = Arc::new(nn);
+        let handles: Vec<_> = (0..4).map(|_| {
+            let nn = Arc::clone(&nn);
+            let inputs = dataset.get_inputs().clone();
+            thread::spawn(move || inputs.iter().map(|x| nn.predict(x).unwrap()).collect::<Vec<_>>())
+        }).collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_fit_parallel() {
+        let dataset = dataset();
+        let config = TrainConfig::new(3).batch_size(4).shuffle(true).seed(7).workers(3);
+        let mut serial = build().unwrap();
+        let initial = serial.get_weights();
+        let serial_history = serial.fit(&dataset, &TrainConfig::new(3).batch_size(4).shuffle(true).seed(7)).unwrap();
+        let mut runs = Vec::new();
+        for _ in 0..2 {
+            let mut nn = build().unwrap();
+            nn.set_weights(initial.clone()).unwrap();
+            let history = nn.fit_parallel(&dataset, &config, build, &mut []).unwrap();
+            for (a, b) in history.loss.iter().zip(serial_history.loss.iter()) {
+                assert!((a - b).abs() < 1e-12);
+            }
+            runs.push(nn.get_weights());
+        }
+        // identical seed and worker count give bit-identical weights
+        assert_eq!(runs[0], runs[1]);
+        let mut routed = build().unwrap();
+        routed.set_weights(initial.clone()).unwrap();
+        routed.fit(&dataset, &config).unwrap();
+        assert_eq!(routed.get_weights(), runs[0]);
+        for (a, b) in runs[0].iter().zip(serial.get_weights().iter()) {
+            for (x, y) in a.get_data().iter().zip(b.get_data().iter()) {
+                assert!((x - y).abs() < 1e-12);
+            }
+        }
+        let mut nn = build().unwrap();
+        assert!(nn.fit_parallel(&dataset, &config.workers(0), build, &mut []).is_err());
+        let mismatched = || {
+            let mut nn = NN::new([1, 2], 0.5);
+            nn.add(Box::new(DenseLayer::new(2)))?;
+            Ok(nn)
+        };
+        assert!(nn.fit_parallel(&dataset, &TrainConfig::new(1).workers(2), mismatched, &mut []).is_err());
+        assert!(nn.fit(&dataset, &TrainConfig::new(1).workers(0)).is_err());
+
+        let self_updating = || {
+            let mut nn = build()?;
+            nn.add(Box::new(Identity))?;
+            Ok(nn)
+        };
+        let mut nn = self_updating().unwrap();
+        let before = nn.get_weights();
+        let result = nn.fit_parallel(&dataset, &TrainConfig::new(1).workers(2), self_updating, &mut []);
+        assert!(matches!(result, Err(Error::InvalidConfiguration(_))));
+        assert_eq!(nn.get_weights(), before);
+        assert!(nn.fit(&dataset, &TrainConfig::new(1).workers(2)).is_err());
+    }
+
+    #[test]
+    fn test_summary() {
+        let summary = build().unwrap().get_summary();
+        let names: Vec<&str> = summary.layers.iter().map(|layer| layer.name.as_str()).collect();
+        assert_eq!(names, ["dense", "activation(sigmoid)", "dense"]);
+        assert_eq!(summary.layers[0].input_size, [1, 2]);
+        assert_eq!(summary.layers[1].output_size, [1, 3]);
+        assert_eq!(summary.layers.iter().map(|layer| layer.trainable_parameters).collect::<Vec<_>>(), [9, 0, 4]);
+        assert_eq!(summary.total_parameters(), 13);
+        assert_eq!(summary.parameter_memory(), 13 * 8);
+        assert_eq!(summary.activation_memory(), (2 + 3 + 3 + 1) * 8);
+        let table = summary.to_string();
+        assert!(table.contains("activation(sigmoid)"));
+        assert!(table.contains("Total parameters: 13 (trainable 13, non-trainable 0)"));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!("nn_model_{}.json", std::process::id()));
+        let mut nn = build().unwrap();
+        nn.train(&dataset(), 2).unwrap();
+        nn.save(&path).unwrap();
+        let loaded: NN = NN::load(&path).unwrap();
+        assert_eq!(loaded.get_config().unwrap(), nn.get_config().unwrap());
+        assert_eq!(loaded.get_weights(), nn.get_weights());
+        std::fs::remove_file(&path).unwrap();
     }
 }
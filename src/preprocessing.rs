@@ -0,0 +1,251 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::error::{Error, Result};
+use crate::float::Float;
+use crate::matrix::{Matrix, SymmetricEigen};
+
+/// Scaling applied after the PCA projection. `epsilon` is added to every
+/// eigenvalue before taking the inverse square root.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Whitening {
+    None,
+    Pca {epsilon: f64},
+    Zca {epsilon: f64}
+}
+
+/// Principal component analysis fitted on a set of equally shaped samples, which
+/// are treated as flattened row vectors.
+///
+/// `transform` yields a `[1, n_components]` matrix, except with `Whitening::Zca`
+/// where the whitened sample is rotated back into the input space and keeps its shape.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(deserialize = ""))]
+pub struct Pca<T: Float = f64> {
+    input_shape: [usize; 2],
+    mean: Matrix<T>,
+    components: Matrix<T>,
+    explained_variance: Vec<T>,
+    total_variance: T,
+    whitening: Whitening
+}
+
+impl<T: Float> Pca<T> {
+    pub fn fit(data: &[Matrix<T>], n_components: usize, whitening: Whitening) -> Result<Pca<T>> {
+        let Some(first) = data.first() else {
+            return Err(Error::InvalidConfiguration("Cannot fit PCA on an empty dataset".to_string()));
+        };
+        let input_shape = first.get_shape();
+        let features = input_shape[0] * input_shape[1];
+        if n_components == 0 || n_components > features {
+            return Err(Error::InvalidConfiguration(
+                format!("Number of PCA components must be between 1 and {}, got {}", features, n_components)));
+        }
+        let mut mean = vec![T::zero(); features];
+        for sample in data {
+            if sample.get_shape() != input_shape {
+                return Err(Error::shape_mismatch("Pca::fit", input_shape, sample.get_shape()));
+            }
+            mean.iter_mut().zip(sample.get_data().iter()).for_each(|(m, &x)| *m += x);
+        }
+        let count = T::from_usize(data.len());
+        mean.iter_mut().for_each(|m| *m /= count);
+
+        let mut covariance: Matrix<T> = Matrix::new(features, features);
+        let mut centered = vec![T::zero(); features];
+        for sample in data {
+            centered.iter_mut().zip(sample.get_data().iter().zip(mean.iter())).for_each(|(c, (&x, &m))| *c = x - m);
+            let covariance_data = covariance.get_data_mut();
+            for i in 0..features {
+                for j in i..features {
+                    covariance_data[i*features + j] += centered[i] * centered[j];
+                }
+            }
+        }
+        let degrees_of_freedom = T::from_usize(data.len().saturating_sub(1).max(1));
+        for i in 0..features {
+            for j in i..features {
+                let value = covariance[(i, j)] / degrees_of_freedom;
+                covariance[(i, j)] = value;
+                covariance[(j, i)] = value;
+            }
+        }
+        let total_variance = (0..features).map(|i| covariance[(i, i)]).sum();
+
+        let SymmetricEigen {eigenvalues, eigenvectors} = covariance.symmetric_eigen()?;
+        let components = eigenvectors.slice(0..features, 0..n_components)?.t().to_matrix();
+        let explained_variance = eigenvalues.into_iter().take(n_components).map(|x| x.max(T::zero())).collect();
+        Ok(Pca {
+            input_shape,
+            mean: Matrix::from_vec(mean, 1, features)?,
+            components,
+            explained_variance,
+            total_variance,
+            whitening
+        })
+    }
+
+    pub fn get_num_components(&self) -> usize {
+        self.components.get_num_rows()
+    }
+
+    pub fn get_input_shape(&self) -> [usize; 2] {
+        self.input_shape
+    }
+
+    pub fn get_mean(&self) -> &Matrix<T> {
+        &self.mean
+    }
+
+    /// Principal axes as rows, ordered by decreasing explained variance.
+    pub fn get_components(&self) -> &Matrix<T> {
+        &self.components
+    }
+
+    pub fn get_explained_variance(&self) -> &Vec<T> {
+        &self.explained_variance
+    }
+
+    pub fn get_explained_variance_ratio(&self) -> Vec<T> {
+        self.explained_variance.iter().map(|&x| x / self.total_variance).collect()
+    }
+
+    pub fn get_whitening(&self) -> Whitening {
+        self.whitening
+    }
+
+    fn whitening_scale(&self) -> Option<Vec<T>> {
+        match self.whitening {
+            Whitening::None => None,
+            Whitening::Pca {epsilon} | Whitening::Zca {epsilon} =>
+                Some(self.explained_variance.iter().map(|&x| (x + T::from_f64(epsilon)).sqrt()).collect())
+        }
+    }
+
+    pub fn transform(&self, input: &Matrix<T>) -> Result<Matrix<T>> {
+        if input.get_shape() != self.input_shape {
+            return Err(Error::shape_mismatch("Pca::transform", self.input_shape, input.get_shape()));
+        }
+        let features = self.mean.get_num_cols();
+        let mut centered = input.clone().reshape(1, features)?;
+        centered.sub_matrix(&self.mean)?;
+        let mut projected = centered.matmul(self.components.t())?;
+        if let Some(scale) = self.whitening_scale() {
+            projected.get_data_mut().iter_mut().zip(scale.iter()).for_each(|(x, &s)| *x /= s);
+        }
+        match self.whitening {
+            Whitening::Zca {..} => projected.matmul(&self.components)?.reshape(self.input_shape[0], self.input_shape[1]),
+            _ => Ok(projected)
+        }
+    }
+
+    pub fn inverse_transform(&self, projected: &Matrix<T>) -> Result<Matrix<T>> {
+        let mut projected = match self.whitening {
+            Whitening::Zca {..} => {
+                if projected.get_shape() != self.input_shape {
+                    return Err(Error::shape_mismatch("Pca::inverse_transform", self.input_shape, projected.get_shape()));
+                }
+                projected.clone().reshape(1, self.mean.get_num_cols())?.matmul(self.components.t())?
+            },
+            _ => {
+                if projected.get_shape() != [1, self.get_num_components()] {
+                    return Err(Error::shape_mismatch("Pca::inverse_transform", [1, self.get_num_components()], projected.get_shape()));
+                }
+                projected.clone()
+            }
+        };
+        if let Some(scale) = self.whitening_scale() {
+            projected.get_data_mut().iter_mut().zip(scale.iter()).for_each(|(x, &s)| *x *= s);
+        }
+        let mut reconstructed = projected.matmul(&self.components)?;
+        reconstructed.add_matrix(&self.mean)?;
+        reconstructed.reshape(self.input_shape[0], self.input_shape[1])
+    }
+
+    pub fn transform_all(&self, data: &[Matrix<T>]) -> Result<Vec<Matrix<T>>> {
+        data.iter().map(|sample| self.transform(sample)).collect()
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(writer, self)?;
+        Ok(())
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Pca<T>> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
+
+#[cfg(test)]
+mod test_pca {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn data() -> Vec<Matrix> {
+        [[2.5, 2.4, 0.3], [0.5, 0.7, -0.1], [2.2, 2.9, 0.4], [1.9, 2.2, 0.0], [3.1, 3.0, 0.2], [2.3, 2.7, 0.5], [1.0, 1.1, -0.3]]
+            .iter().map(|row| Matrix::from_vec(row.to_vec(), 1, 3).unwrap()).collect()
+    }
+
+    fn assert_close(a: &Matrix, b: &Matrix, tolerance: f64) {
+        assert_eq!(a.get_shape(), b.get_shape());
+        for (x, y) in a.get_data().iter().zip(b.get_data().iter()) {
+            assert!((x - y).abs() < tolerance, "{}\n!=\n{}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_projection_round_trip() {
+        let data = data();
+        let full = Pca::fit(&data, 3, Whitening::None).unwrap();
+        for sample in data.iter() {
+            assert_close(&full.inverse_transform(&full.transform(sample).unwrap()).unwrap(), sample, 1e-9);
+        }
+        let reduced = Pca::fit(&data, 1, Whitening::None).unwrap();
+        assert_eq!(reduced.transform(&data[0]).unwrap().get_shape(), [1, 1]);
+        assert!(reduced.get_explained_variance_ratio()[0] > 0.9);
+        assert!(Pca::fit(&data, 4, Whitening::None).is_err());
+    }
+
+    #[test]
+    fn test_whitening() {
+        let data = data();
+        for whitening in [Whitening::Pca {epsilon: 0.0}, Whitening::Zca {epsilon: 0.0}] {
+            let pca = Pca::fit(&data, 3, whitening).unwrap();
+            let transformed = pca.transform_all(&data).unwrap();
+            let refit = Pca::fit(&transformed, 3, Whitening::None).unwrap();
+            for &variance in refit.get_explained_variance() {
+                assert!((variance - 1.0).abs() < 1e-6);
+            }
+            assert_close(&pca.inverse_transform(&transformed[2]).unwrap(), &data[2], 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_wide_input() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let data: Vec<Matrix> = (0..200).map(|_| {
+            let values = (0..120).map(|i| rng.gen_range(-1.0..1.0) * (1.0 + (i % 10) as f64)).collect();
+            Matrix::from_vec(values, 12, 10).unwrap()
+        }).collect();
+        let pca = Pca::fit(&data, 120, Whitening::None).unwrap();
+        let variance = pca.get_explained_variance();
+        assert!(variance.windows(2).all(|w| w[0] >= w[1]));
+        let ratio_sum: f64 = pca.get_explained_variance_ratio().iter().sum();
+        assert!((ratio_sum - 1.0).abs() < 1e-9);
+        let reduced = Pca::fit(&data, 20, Whitening::None).unwrap();
+        assert_eq!(reduced.get_explained_variance(), &variance[..20].to_vec());
+        assert!(reduced.get_explained_variance_ratio().iter().sum::<f64>() < 1.0);
+    }
+
+    #[test]
+    fn test_serialization() {
+        let pca = Pca::fit(&data(), 2, Whitening::Zca {epsilon: 1e-5}).unwrap();
+        let restored: Pca = serde_json::from_str(&serde_json::to_string(&pca).unwrap()).unwrap();
+        assert_eq!(restored.transform(&data()[0]).unwrap(), pca.transform(&data()[0]).unwrap());
+        assert!(serde_json::from_str::<Matrix>(r#"{"rows": 2, "cols": 2, "data": [1.0]}"#).is_err());
+    }
+}
@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+use crate::error::Result;
+use crate::float::Float;
+use crate::matrix::{Axis, Matrix, Norm};
+
+/// Weight penalty added to the training loss.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Regularizer {
+    /// `l1 * sum(|w|)`
+    L1(f64),
+    /// `l2 * sum(w^2)`
+    L2(f64),
+    ElasticNet {l1: f64, l2: f64}
+}
+
+impl Regularizer {
+    fn coefficients(&self) -> (f64, f64) {
+        match *self {
+            Regularizer::L1(l1) => (l1, 0.0),
+            Regularizer::L2(l2) => (0.0, l2),
+            Regularizer::ElasticNet {l1, l2} => (l1, l2)
+        }
+    }
+
+    pub fn penalty<T: Float>(&self, weights: &Matrix<T>) -> T {
+        let (l1, l2) = self.coefficients();
+        weights.get_data().iter().map(|&w| T::from_f64(l1) * w.abs() + T::from_f64(l2) * w * w).sum()
+    }
+
+    pub fn gradient<T: Float>(&self, weights: &Matrix<T>) -> Matrix<T> {
+        let (l1, l2) = self.coefficients();
+        let mut gradient = weights.clone();
+        gradient.get_data_mut().iter_mut().for_each(|w| {
+            let sign = if *w > T::zero() {T::one()} else if *w < T::zero() {-T::one()} else {T::zero()};
+            *w = T::from_f64(l1) * sign + T::from_f64(2.0 * l2) * *w;
+        });
+        gradient
+    }
+}
+
+/// Projection applied to a weight matrix after every update. Norm constraints act
+/// on each row or column when an axis is given, otherwise on the whole matrix;
+/// `Some(Axis::Cols)` constrains the incoming weights of every `DenseLayer` unit.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Constraint {
+    MaxNorm {max_value: f64, axis: Option<Axis>},
+    UnitNorm {axis: Option<Axis>},
+    NonNegative
+}
+
+fn rescale<T: Float>(weights: &mut Matrix<T>, axis: Option<Axis>, factor: impl Fn(T) -> T) -> Result<()> {
+    let Some(axis) = axis else {
+        *weights *= factor(weights.norm(Norm::Frobenius));
+        return Ok(());
+    };
+    let mut factors = weights.norm_axis(Norm::L2, axis);
+    factors.get_data_mut().iter_mut().for_each(|norm| *norm = factor(*norm));
+    weights.elementwise_mul(&factors)?;
+    Ok(())
+}
+
+impl Constraint {
+    pub fn apply<T: Float>(&self, weights: &mut Matrix<T>) -> Result<()> {
+        match *self {
+            Constraint::MaxNorm {max_value, axis} => {
+                let max_value = T::from_f64(max_value);
+                rescale(weights, axis, |norm| if norm > max_value {max_value / norm} else {T::one()})
+            },
+            Constraint::UnitNorm {axis} => rescale(weights, axis, |norm| T::one() / (norm + T::epsilon())),
+            Constraint::NonNegative => {
+                weights.get_data_mut().iter_mut().for_each(|w| *w = w.max(T::zero()));
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_regularization {
+    use super::*;
+
+    #[test]
+    fn test_regularizers() {
+        let weights = Matrix::from_vec(vec![1.0, -2.0, 0.0, 3.0], 2, 2).unwrap();
+        let elastic_net = Regularizer::ElasticNet {l1: 0.5, l2: 0.25};
+        assert_eq!(Regularizer::L1(0.5).penalty(&weights), 3.0);
+        assert_eq!(Regularizer::L2(0.25).penalty(&weights), 3.5);
+        assert_eq!(elastic_net.penalty(&weights), 6.5);
+        assert_eq!(elastic_net.gradient(&weights).get_data(), &vec![1.0, -1.5, 0.0, 2.0]);
+    }
+
+    #[test]
+    fn test_constraints() {
+        let weights = Matrix::from_vec(vec![3.0, 0.5, -4.0, 0.0], 2, 2).unwrap();
+        let mut max_norm = weights.clone();
+        Constraint::MaxNorm {max_value: 1.0, axis: Some(Axis::Cols)}.apply(&mut max_norm).unwrap();
+        for (x, y) in max_norm.get_data().iter().zip([0.6, 0.5, -0.8, 0.0]) {
+            assert!((x - y).abs() < 1e-12);
+        }
+        let mut unit_norm = weights.clone();
+        Constraint::UnitNorm {axis: None}.apply(&mut unit_norm).unwrap();
+        assert!((unit_norm.norm(Norm::Frobenius) - 1.0).abs() < 1e-12);
+        let mut non_negative = weights;
+        Constraint::NonNegative.apply(&mut non_negative).unwrap();
+        assert_eq!(non_negative.get_data(), &vec![3.0, 0.5, 0.0, 0.0]);
+    }
+}
@@ -0,0 +1,101 @@
+use std::fmt;
+
+/// Shapes, parameter counts and memory of one layer, as listed by `NN::summary`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LayerSummary {
+    pub name: String,
+    pub input_size: [usize; 2],
+    pub output_size: [usize; 2],
+    pub trainable_parameters: usize,
+    pub non_trainable_parameters: usize,
+    /// Bytes held by the trainable and non-trainable parameters.
+    pub parameter_memory: usize,
+    /// Bytes of the layer's output for a single sample.
+    pub activation_memory: usize
+}
+
+impl LayerSummary {
+    pub fn total_parameters(&self) -> usize {
+        self.trainable_parameters + self.non_trainable_parameters
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Summary {
+    pub input_size: [usize; 2],
+    pub layers: Vec<LayerSummary>,
+    /// Bytes of a single input sample.
+    pub input_memory: usize
+}
+
+impl Summary {
+    pub fn trainable_parameters(&self) -> usize {
+        self.layers.iter().map(|layer| layer.trainable_parameters).sum()
+    }
+
+    pub fn non_trainable_parameters(&self) -> usize {
+        self.layers.iter().map(|layer| layer.non_trainable_parameters).sum()
+    }
+
+    pub fn total_parameters(&self) -> usize {
+        self.trainable_parameters() + self.non_trainable_parameters()
+    }
+
+    pub fn parameter_memory(&self) -> usize {
+        self.layers.iter().map(|layer| layer.parameter_memory).sum()
+    }
+
+    /// Bytes of the input and every layer's output for one sample in a forward pass.
+    pub fn activation_memory(&self) -> usize {
+        self.input_memory + self.layers.iter().map(|layer| layer.activation_memory).sum::<usize>()
+    }
+}
+
+fn format_shape(shape: [usize; 2]) -> String {
+    format!("[{}, {}]", shape[0], shape[1])
+}
+
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.2} {}", value, UNITS[unit])
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let width = self.layers.iter().map(|layer| layer.name.len()).max().unwrap_or(0).max("Layer".len());
+        let header = format!("{:<w$}  {:>12}  {:>12}  {:>9}  {:>13}", "Layer", "Input shape", "Output shape", "Trainable",
+            "Non-trainable", w = width);
+        writeln!(f, "{}", header)?;
+        writeln!(f, "{}", "-".repeat(header.len()))?;
+        for layer in self.layers.iter() {
+            writeln!(f, "{:<w$}  {:>12}  {:>12}  {:>9}  {:>13}", layer.name, format_shape(layer.input_size),
+                format_shape(layer.output_size), layer.trainable_parameters, layer.non_trainable_parameters, w = width)?;
+        }
+        writeln!(f, "{}", "-".repeat(header.len()))?;
+        writeln!(f, "Total parameters: {} (trainable {}, non-trainable {})", self.total_parameters(),
+            self.trainable_parameters(), self.non_trainable_parameters())?;
+        writeln!(f, "Parameter memory: {}", format_bytes(self.parameter_memory()))?;
+        write!(f, "Activation memory per sample: {}", format_bytes(self.activation_memory()))
+    }
+}
+
+#[cfg(test)]
+mod test_summary {
+    use super::*;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.50 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.00 MiB");
+    }
+}
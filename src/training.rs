@@ -0,0 +1,206 @@
+use crate::clipping::GradientClipping;
+use crate::data_loader::DataLoader;
+use crate::dataset::{Dataset, Subset};
+use crate::error::{Error, Result};
+use crate::float::Float;
+use crate::metrics::Metric;
+use crate::neural_network::NN;
+
+/// Options for `NN::fit`. The last `validation_split` fraction of the dataset is
+/// held out before shuffling and evaluated after every epoch.
+pub struct TrainConfig<T: Float = f64> {
+    epochs: u64,
+    batch_size: usize,
+    shuffle: bool,
+    seed: Option<u64>,
+    validation_split: f64,
+    metrics: Vec<Box<dyn Metric<T>>>,
+    gradient_clipping: GradientClipping<T>,
+    accumulation_steps: usize,
+    workers: usize
+}
+
+impl<T: Float> TrainConfig<T> {
+    pub fn new(epochs: u64) -> TrainConfig<T> {
+        TrainConfig {epochs, batch_size: 1, shuffle: false, seed: None, validation_split: 0.0, metrics: Vec::new(),
+            gradient_clipping: GradientClipping::new(), accumulation_steps: 1, workers: 1}
+    }
+
+    pub fn batch_size(mut self, batch_size: usize) -> TrainConfig<T> {
+        self.batch_size = batch_size;
+        self
+    }
+
+    pub fn shuffle(mut self, shuffle: bool) -> TrainConfig<T> {
+        self.shuffle = shuffle;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> TrainConfig<T> {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn validation_split(mut self, validation_split: f64) -> TrainConfig<T> {
+        self.validation_split = validation_split;
+        self
+    }
+
+    pub fn metric(mut self, metric: Box<dyn Metric<T>>) -> TrainConfig<T> {
+        self.metrics.push(metric);
+        self
+    }
+
+    pub fn gradient_clipping(mut self, gradient_clipping: GradientClipping<T>) -> TrainConfig<T> {
+        self.gradient_clipping = gradient_clipping;
+        self
+    }
+
+    /// Number of batches whose gradients are accumulated before each update. The
+    /// update uses the mean gradient over all their samples, as one larger batch would.
+    pub fn accumulation_steps(mut self, accumulation_steps: usize) -> TrainConfig<T> {
+        self.accumulation_steps = accumulation_steps;
+        self
+    }
+
+    /// Number of training threads. Above one, `NN::fit` trains data-parallel like
+    /// `NN::fit_parallel`. Each batch is split into this many contiguous shards, so
+    /// results depend only on the seed and this count.
+    pub fn workers(mut self, workers: usize) -> TrainConfig<T> {
+        self.workers = workers;
+        self
+    }
+
+    pub fn get_epochs(&self) -> u64 {
+        self.epochs
+    }
+
+    pub fn get_batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    pub fn get_validation_split(&self) -> f64 {
+        self.validation_split
+    }
+
+    pub fn get_metrics(&self) -> &Vec<Box<dyn Metric<T>>> {
+        &self.metrics
+    }
+
+    pub fn get_gradient_clipping(&self) -> &GradientClipping<T> {
+        &self.gradient_clipping
+    }
+
+    pub fn get_accumulation_steps(&self) -> usize {
+        self.accumulation_steps
+    }
+
+    pub fn get_workers(&self) -> usize {
+        self.workers
+    }
+
+    pub fn loader<'a, D: Dataset<T> + ?Sized>(&self, dataset: &'a D) -> Result<DataLoader<'a, T, D>> {
+        let loader = DataLoader::new(dataset, self.batch_size)?.shuffle(self.shuffle);
+        Ok(match self.seed {
+            Some(seed) => loader.seed(seed),
+            None => loader
+        })
+    }
+
+    /// Number of leading samples used for training, the rest being the validation set.
+    pub(crate) fn num_train_samples(&self, len: usize) -> Result<usize> {
+        if !(0.0..1.0).contains(&self.validation_split) {
+            return Err(Error::InvalidConfiguration(
+                format!("Validation split must be in [0, 1), got {}", self.validation_split)));
+        }
+        let num_train = ((len as f64) * (1.0 - self.validation_split)).round() as usize;
+        if self.validation_split > 0.0 && num_train == len {
+            return Err(Error::InvalidConfiguration("Validation split leaves no validation samples".to_string()));
+        }
+        Ok(num_train)
+    }
+}
+
+/// Mean loss and metric scores over a dataset.
+#[derive(Clone, Debug)]
+pub struct Evaluation<T: Float = f64> {
+    pub loss: T,
+    pub metrics: Vec<(String, T)>
+}
+
+impl<T: Float> Evaluation<T> {
+    pub fn get_metric(&self, name: &str) -> Option<T> {
+        self.metrics.iter().find(|(metric, _)| metric == name).map(|&(_, value)| value)
+    }
+}
+
+/// Per-epoch training loss and, when a validation split is used, validation results.
+#[derive(Clone, Debug, Default)]
+pub struct History<T: Float = f64> {
+    pub loss: Vec<T>,
+    pub validation: Vec<Evaluation<T>>
+}
+
+impl<T: Float> History<T> {
+    pub fn get_validation_loss(&self) -> Vec<T> {
+        self.validation.iter().map(|evaluation| evaluation.loss).collect()
+    }
+
+    pub fn get_validation_metric(&self, name: &str) -> Option<Vec<T>> {
+        self.validation.iter().map(|evaluation| evaluation.get_metric(name)).collect()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CrossValidation<T: Float = f64> {
+    pub histories: Vec<History<T>>,
+    pub folds: Vec<Evaluation<T>>
+}
+
+impl<T: Float> CrossValidation<T> {
+    fn aggregate(&self, f: impl Fn(&[T]) -> T) -> Evaluation<T> {
+        let losses: Vec<T> = self.folds.iter().map(|fold| fold.loss).collect();
+        let metrics = self.folds[0].metrics.iter().enumerate().map(|(i, (name, _))| {
+            let values: Vec<T> = self.folds.iter().map(|fold| fold.metrics[i].1).collect();
+            (name.clone(), f(&values))
+        }).collect();
+        Evaluation {loss: f(&losses), metrics}
+    }
+
+    pub fn mean(&self) -> Evaluation<T> {
+        self.aggregate(mean)
+    }
+
+    /// Population standard deviation of every score across the folds.
+    pub fn std(&self) -> Evaluation<T> {
+        self.aggregate(|values| {
+            let mean = mean(values);
+            (values.iter().map(|&x| (x - mean).powi(2)).sum::<T>() / T::from_usize(values.len())).sqrt()
+        })
+    }
+}
+
+fn mean<T: Float>(values: &[T]) -> T {
+    values.iter().copied().sum::<T>() / T::from_usize(values.len())
+}
+
+/// Splits `dataset` into `k` contiguous folds and, for each fold, trains a fresh
+/// model from `build_fn` on the remaining folds and evaluates it on the held-out one.
+pub fn cross_validate<T, D, F>(k: usize, mut build_fn: F, dataset: &D, config: &TrainConfig<T>) -> Result<CrossValidation<T>>
+    where T: Float, D: Dataset<T> + ?Sized, F: FnMut() -> Result<NN<T>> {
+    let len = dataset.len();
+    if k < 2 || k > len {
+        return Err(Error::InvalidConfiguration(format!("Number of folds must be between 2 and {}, got {}", len, k)));
+    }
+    let mut result = CrossValidation {histories: Vec::with_capacity(k), folds: Vec::with_capacity(k)};
+    for fold in 0..k {
+        let start = fold * len / k;
+        let end = (fold + 1) * len / k;
+        let train = Subset::new(dataset, (0..start).chain(end..len).collect())?;
+        let held_out = Subset::new(dataset, (start..end).collect())?;
+        let mut nn = build_fn()?;
+        result.histories.push(nn.fit(&train, config)?);
+        result.folds.push(nn.evaluate(&held_out, config.get_metrics())?);
+    }
+    Ok(result)
+}